@@ -31,7 +31,6 @@
         nativeBuildInputs = with pkgs; [
           rust-bin.stable.latest.default
           pkg-config
-          makeWrapper
         ];
 
       in
@@ -44,14 +43,6 @@
             lockFile = ./Cargo.lock;
           };
           inherit buildInputs nativeBuildInputs;
-          postInstall = ''
-            mkdir -p $out/share/typy
-            cp $src/resources/lang/english.txt $out/share/typy/english.txt
-
-            wrapProgram $out/bin/typy \
-            --run "mkdir -p ~/.local/share/typy" \
-            --run "cp -n $out/share/typy/english.txt ~/.local/share/typy/english.txt"
-          '';
 
           meta = with pkgs.lib; {
             description = "typy-cli - Minimalistic Monkeytype clone for the CLI";
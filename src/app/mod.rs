@@ -6,14 +6,23 @@ use crossterm::cursor::SetCursorStyle;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 
-use crate::config::save_settings;
+use crate::clock::SharedClock;
+use crate::config::afk::AfkAction;
+use crate::config::check::check_config;
+use crate::config::model::TIME_PRESETS;
+use crate::config::toml_parser::Profile;
+use crate::config::watch::Watcher;
+use crate::config::{save_profile, save_settings, Settings};
+use crate::keymap::{Action, Context, QUICK_DIGITS};
 use crate::mode::Mode;
+use crate::palette::{Command, PaletteState};
 use crate::scores::progress::{Averages, Data, Score};
-use crate::settings::SettingsState;
+use crate::settings::{mode_options, Preferences, SettingsState};
 use crate::theme::{self, Theme};
 use crate::tui::{events, Tui};
 use crate::typing::TypingSession;
 use crate::ui;
+use crate::word_provider::{FileText, MarkovText, TextStyle, WordFilter, WordList, WordSource};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
@@ -27,6 +36,65 @@ pub enum Screen {
 pub struct StatsData {
     pub averages: Averages,
     pub scores: Vec<Score>,
+    /// The profiles the scores were played with; `None` is no profile.
+    pub profiles: Vec<Option<String>>,
+    /// Shows only the scores of `profiles[i]`; `None` shows all of them.
+    pub filter: Option<usize>,
+}
+
+impl StatsData {
+    fn new(averages: Averages, scores: Vec<Score>) -> Self {
+        let mut profiles: Vec<Option<String>> = scores.iter().map(|s| s.profile.clone()).collect();
+        profiles.sort();
+        profiles.dedup();
+        StatsData {
+            averages,
+            scores,
+            profiles,
+            filter: None,
+        }
+    }
+
+    /// The scores that pass the profile filter, newest first.
+    pub fn visible(&self) -> Vec<&Score> {
+        match self.filter {
+            Some(i) => self
+                .scores
+                .iter()
+                .filter(|s| s.profile == self.profiles[i])
+                .collect(),
+            None => self.scores.iter().collect(),
+        }
+    }
+
+    /// The averages of all games, or of the filtered recent ones.
+    pub fn visible_averages(&self) -> Averages {
+        match self.filter {
+            Some(_) => Averages::of(self.visible()),
+            None => self.averages.clone(),
+        }
+    }
+
+    pub fn filter_label(&self) -> &str {
+        match self.filter {
+            Some(i) => self.profiles[i].as_deref().unwrap_or("no profile"),
+            None => "all profiles",
+        }
+    }
+
+    /// Whether any of the scores was played with a profile.
+    pub fn has_profiles(&self) -> bool {
+        self.profiles.iter().any(Option::is_some)
+    }
+
+    /// Steps through all profiles, then back to showing every score.
+    pub fn next_filter(&mut self) {
+        self.filter = match self.filter {
+            None if !self.profiles.is_empty() => Some(0),
+            Some(i) if i + 1 < self.profiles.len() => Some(i + 1),
+            _ => None,
+        };
+    }
 }
 
 pub struct App {
@@ -36,56 +104,158 @@ pub struct App {
     pub theme_name: String,
     pub cursor_style: String,
     pub language: String,
+    pub top_words: usize,
+    pub lazy: bool,
+    pub text_style: TextStyle,
+    pub filter: WordFilter,
     pub mode_tokens: Vec<String>,
     pub time: u64,
+    /// The config file's settings. The fields above start out from them and
+    /// may be changed for this run only, e.g. by the quick menu or flags.
+    pub config: Settings,
     pub session: Option<TypingSession>,
     pub settings: Option<SettingsState>,
     pub stats: Option<StatsData>,
+    pub quick: bool,
+    pub help: bool,
+    pub palette: Option<PaletteState>,
+    pub notice: Option<String>,
     pub direct: bool,
     pub record: u32,
     pub previous_record: u32,
     pub new_record: bool,
+    text: Option<String>,
+    persist: bool,
+    clock: SharedClock,
     results_opened: Option<Instant>,
+    /// The filtered list of the current language, loaded once per language.
+    words: Option<(String, WordList)>,
+    /// Notices edits to the config and theme files; `None` unless persisting.
+    watcher: Option<Watcher>,
+    /// Whether the running test was paused because the terminal lost focus,
+    /// so regaining it resumes the test.
+    focus_paused: bool,
+    /// Whether the running test was paused by opening the quick menu, so
+    /// closing it resumes the test.
+    quick_paused: bool,
+}
+
+/// Everything the app needs to start, resolved from the CLI and config.
+pub struct AppConfig {
+    pub language: String,
+    /// Restricts ranked word lists to their most frequent words; `0` is all.
+    pub top_words: usize,
+    /// Accepts letters typed without their accents.
+    pub lazy: bool,
+    /// Random words or generated sentences.
+    pub text_style: TextStyle,
+    pub filter: WordFilter,
+    pub mode_tokens: Vec<String>,
+    pub time: u64,
+    pub direct: bool,
+    /// Fixed text to type instead of random words.
+    pub text: Option<String>,
+    /// Whether scores and settings are written to disk.
+    pub persist: bool,
+    pub clock: SharedClock,
+    /// Shown on the home screen until the first key press.
+    pub notice: Option<String>,
+    /// What the config file says; theme, cursor and key bindings come from
+    /// here.
+    pub settings: Settings,
 }
 
 impl App {
-    pub fn new(
-        theme: Theme,
-        cursor_style: String,
-        language: String,
-        mode_tokens: Vec<String>,
-        time: u64,
-        direct: bool,
-    ) -> Self {
+    pub fn new(config: AppConfig) -> Self {
+        let AppConfig {
+            language,
+            top_words,
+            lazy,
+            text_style,
+            filter,
+            mode_tokens,
+            time,
+            direct,
+            text,
+            persist,
+            clock,
+            notice,
+            settings,
+        } = config;
         App {
             screen: Screen::Home,
             should_quit: false,
-            theme_name: theme.name.clone(),
-            theme,
-            cursor_style,
+            theme: theme::load(&settings.theme),
+            theme_name: settings.theme.clone(),
+            cursor_style: settings.cursor.clone(),
             language,
+            top_words,
+            lazy,
+            text_style,
+            filter,
             mode_tokens,
             time,
+            config: settings,
             session: None,
             settings: None,
             stats: None,
+            quick: false,
+            help: false,
+            palette: None,
+            notice: notice.filter(|_| !direct),
             direct,
-            record: Data::get_record(time).unwrap_or(0),
+            record: if persist {
+                Data::get_record(time).unwrap_or(0)
+            } else {
+                0
+            },
             previous_record: 0,
             new_record: false,
+            text,
+            persist,
+            clock,
             results_opened: None,
+            words: None,
+            watcher: if persist && !direct {
+                Watcher::new()
+            } else {
+                None
+            },
+            focus_paused: false,
+            quick_paused: false,
         }
     }
 
     fn refresh_record(&mut self) {
+        if !self.persist {
+            return;
+        }
         self.record = Data::get_record(self.time).unwrap_or(0);
     }
 
-    fn tick(&mut self) {
+    pub(crate) fn tick(&mut self) {
         if self.screen != Screen::Typing {
             return;
         }
         if let Some(session) = self.session.as_mut() {
+            let afk = self.config.afk;
+            if afk.timeout > 0 && session.idle_secs() >= afk.timeout {
+                match afk.action {
+                    AfkAction::Pause => session.pause_idle(),
+                    AfkAction::Invalidate => {
+                        self.session = None;
+                        self.notice = Some("test invalidated: no input for too long".to_string());
+                        // a test started from the command line exits, like
+                        // it does once finished
+                        if self.direct {
+                            self.should_quit = true;
+                        } else {
+                            self.screen = Screen::Home;
+                        }
+                        return;
+                    }
+                }
+            }
             session.tick();
             if session.is_finished() {
                 self.finish_test();
@@ -93,127 +263,554 @@ impl App {
         }
     }
 
-    fn start_test(&mut self) {
-        let mode = Mode::from_str(self.mode_tokens.iter().map(|s| s.as_str()).collect())
-            .unwrap_or_else(|_| Mode::from_str(vec!["normal"]).unwrap())
-            .add_duration(self.time);
+    pub(crate) fn start_test(&mut self) {
+        // Fixed text is typed as written, so modes only apply to word lists.
+        let (source, mode_tokens): (Result<Box<dyn WordSource>>, Vec<&str>) = match &self.text {
+            Some(text) => (Ok(Box::new(FileText::new(text.as_str()))), vec!["normal"]),
+            // Generated sentences bring their own punctuation.
+            None => (
+                self.text_source(),
+                self.mode_tokens
+                    .iter()
+                    .map(|s| s.as_str())
+                    .filter(|&m| self.text_style == TextStyle::Words || m != "punctuation")
+                    .collect(),
+            ),
+        };
 
-        match TypingSession::new(&mode, &self.language) {
+        let settings = self.config.modes.clone();
+        let mode = Mode::with_settings(mode_tokens, settings.clone())
+            .unwrap_or_else(|_| Mode::with_settings(vec!["normal"], settings).unwrap())
+            .add_duration(self.time)
+            .add_language(&self.language);
+
+        let rtl = self.word_list().is_ok_and(|list| list.rtl);
+        let session = source
+            .and_then(|mut source| TypingSession::new(&mode, source.as_mut(), self.clock.clone()))
+            .map(|session| session.with_lazy(self.lazy).with_rtl(rtl));
+        match session {
             Ok(session) => {
                 self.session = Some(session);
                 self.screen = Screen::Typing;
             }
-            Err(_) => self.screen = Screen::Home,
+            Err(e) => {
+                self.notice = Some(format!("{e:#}"));
+                self.screen = Screen::Home;
+            }
+        }
+    }
+
+    /// Words for the next test, in the chosen text style.
+    fn text_source(&mut self) -> Result<Box<dyn WordSource>> {
+        match self.text_style {
+            TextStyle::Words => {
+                let top_words = self.top_words;
+                self.word_list()
+                    .and_then(|l| l.clone().top(top_words).into_source())
+            }
+            TextStyle::Sentences => MarkovText::load(&self.language)
+                .map(|source| Box::new(source) as Box<dyn WordSource>),
+        }
+    }
+
+    /// The filtered list of the language, loaded once per language.
+    fn word_list(&mut self) -> Result<&WordList> {
+        let (_, list) = match self.words.take() {
+            Some(words) if words.0 == self.language => self.words.insert(words),
+            _ => {
+                let list = WordList::load(&self.language).and_then(|l| self.filter.apply(l))?;
+                self.words.insert((self.language.clone(), list))
+            }
+        };
+        Ok(list)
+    }
+
+    /// How many words the list of the language ranks; `None` if it has no
+    /// ranking.
+    fn ranked(&mut self) -> Option<usize> {
+        let list = self.word_list().ok()?;
+        list.is_ranked().then_some(list.words.len())
+    }
+
+    fn repeat_test(&mut self) {
+        match self.session.as_ref() {
+            Some(session) => {
+                self.session = Some(session.replay());
+                self.screen = Screen::Typing;
+            }
+            None => self.start_test(),
         }
     }
 
     fn open_stats(&mut self) {
-        let mut scores = Data::get_scores().unwrap_or_default();
+        let (mut scores, averages) = if self.persist {
+            (
+                Data::get_scores().unwrap_or_default(),
+                Data::get_averages().unwrap_or_else(|_| Data::default().averages),
+            )
+        } else {
+            (Vec::new(), Data::default().averages)
+        };
         Score::sort_scores(&mut scores);
-        let averages = Data::get_averages().unwrap_or_else(|_| Data::default().averages);
-        self.stats = Some(StatsData { averages, scores });
+        self.stats = Some(StatsData::new(averages, scores));
         self.screen = Screen::Stats;
     }
 
+    fn preferences(&mut self) -> Preferences<'_> {
+        let ranked = self.ranked();
+        let values = [
+            ("theme", self.theme_name.clone()),
+            ("cursor", self.cursor_style.clone()),
+            ("language.lang", self.language.clone()),
+            ("language.text_style", self.text_style.name().to_string()),
+            ("language.top_words", self.top_words.to_string()),
+            ("language.lazy", self.lazy.to_string()),
+            ("modes.default_mode", self.mode_tokens.join(", ")),
+            ("game.time", self.time.to_string()),
+        ];
+        Preferences {
+            values: values
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value))
+                .collect(),
+            ranked,
+            profile: self.config.profile.as_deref(),
+            profiles: &self.config.profiles,
+        }
+    }
+
     fn open_settings(&mut self) {
-        self.settings = Some(SettingsState::new(
-            &self.theme_name,
-            &self.cursor_style,
-            &self.language,
-            &self.mode_tokens,
-            self.time,
-        ));
+        self.settings = Some(SettingsState::new(&self.preferences()));
         self.screen = Screen::Settings;
     }
 
     fn apply_settings(&mut self) {
-        let Some((theme_name, cursor_style, language, mode_tokens, time, mode_default)) =
-            self.settings.as_ref().map(|s| {
-                (
-                    s.theme_name(),
-                    s.cursor_style(),
-                    s.language(),
-                    s.mode_tokens(),
-                    s.time(),
-                    s.mode_default_string(),
-                )
-            })
-        else {
+        let Some(s) = self.settings.as_ref() else {
             return;
         };
+        let profile = s.profile();
+        if profile != self.config.profile {
+            return self.switch_profile(profile);
+        }
 
-        self.theme_name = theme_name;
+        let value = |key| s.value(key).unwrap_or_default();
+        self.theme_name = value("theme").to_string();
         self.theme = theme::load(&self.theme_name);
-        self.cursor_style = cursor_style;
-        self.language = language;
-        self.mode_tokens = mode_tokens;
-        self.time = time;
+        self.cursor_style = value("cursor").to_string();
+        self.language = value("language.lang").to_string();
+        self.top_words = value("language.top_words").parse().unwrap_or(0);
+        self.lazy = value("language.lazy") == "true";
+        self.text_style = value("language.text_style").parse().unwrap_or_default();
+        self.mode_tokens = value("modes.default_mode")
+            .split(',')
+            .map(|m| m.trim().to_string())
+            .collect();
+        self.time = value("game.time").parse().unwrap_or(self.time);
         self.refresh_record();
-        let _ = save_settings(
-            &self.theme_name,
-            &self.cursor_style,
-            &self.language,
-            &mode_default,
-            self.time,
-        );
+        self.persist();
+        // the words row depends on the language
+        self.refresh_settings();
+    }
+
+    /// Applies another profile, or none, and makes it the one typy starts
+    /// with next time.
+    fn switch_profile(&mut self, profile: Option<String>) {
+        match Settings::load(Profile::of(profile.as_deref())) {
+            Ok(settings) => self.apply_config(settings),
+            Err(e) => {
+                self.notice = Some(format!("{e:#}"));
+                return;
+            }
+        }
+        if self.persist {
+            if let Ok(settings) = save_profile(profile.as_deref()) {
+                self.config = settings;
+            }
+            self.sync_watcher();
+        }
+        self.refresh_settings();
+    }
+
+    /// Shows the current values on an open settings page, keeping the row.
+    fn refresh_settings(&mut self) {
+        if let Some(cursor) = self.settings.as_ref().map(|s| s.cursor) {
+            let mut settings = SettingsState::new(&self.preferences());
+            settings.cursor = cursor;
+            self.settings = Some(settings);
+        }
+    }
+
+    /// Switches to `settings` and takes every value from them, dropping
+    /// what was given on the command line.
+    fn apply_config(&mut self, settings: Settings) {
+        self.config = settings;
+        let config = &self.config;
+        self.theme_name = config.theme.clone();
+        self.theme = theme::load(&self.theme_name);
+        self.cursor_style = config.cursor.clone();
+        self.time = config.time.get();
+        self.language = config.language.lang.clone();
+        self.top_words = config.language.top_words;
+        self.lazy = config.language.lazy;
+        self.text_style = config.language.text_style;
+        self.mode_tokens = config.mode_tokens();
+        self.filter = config.filter.clone();
+        self.words = None;
+        self.refresh_record();
+    }
+
+    fn persist(&mut self) {
+        if !self.persist {
+            return;
+        }
+        if let Ok(settings) = save_settings(&self.preferences()) {
+            self.config = settings;
+        }
+        self.sync_watcher();
+    }
+
+    /// Keeps typy's own writes to the config file from looking like edits.
+    fn sync_watcher(&mut self) {
+        if let Some(watcher) = self.watcher.as_mut() {
+            watcher.sync();
+        }
+    }
+
+    /// Whether edits to the config files may be applied now: on the home
+    /// screen or the settings page, with nothing open on top.
+    fn can_reload(&self) -> bool {
+        let settings_busy = self
+            .settings
+            .as_ref()
+            .is_some_and(|s| s.open || s.input.is_some());
+        match self.screen {
+            Screen::Home => !self.quick && self.palette.is_none(),
+            Screen::Settings => !settings_busy && self.palette.is_none(),
+            _ => false,
+        }
+    }
+
+    /// Applies edits to the config file and `theme.toml`. Edits made
+    /// elsewhere wait until the home screen or settings page is shown.
+    pub(crate) fn watch_config(&mut self) {
+        if !self.can_reload() {
+            return;
+        }
+        let Some(changes) = self.watcher.as_mut().map(Watcher::poll) else {
+            return;
+        };
+        if changes.config {
+            self.config_changed();
+        } else if changes.theme {
+            self.theme = theme::load(&self.theme_name);
+        }
+        if changes.config || changes.theme {
+            self.refresh_settings();
+        }
+    }
+
+    /// Reloads the edited config file, keeping the current profile. Values
+    /// from the command line give way to the file's, and problems in the
+    /// file are shown instead of being ignored.
+    fn config_changed(&mut self) {
+        let summary = check_config().ok().flatten().and_then(|r| r.summary());
+        match Settings::load(Profile::of(self.config.profile.as_deref())) {
+            Ok(settings) => {
+                if settings != self.config {
+                    self.apply_config(settings);
+                }
+                self.theme = theme::load(&self.theme_name);
+                self.notice = Some(match summary {
+                    Some(summary) => format!("config reloaded: {summary}"),
+                    None => "config reloaded".to_string(),
+                });
+            }
+            Err(e) => {
+                self.notice = Some(match summary {
+                    Some(summary) => format!("config not reloaded: {summary}"),
+                    None => format!("config not reloaded: {e:#}"),
+                });
+            }
+        }
+    }
+
+    fn run_command(&mut self, command: Command) {
+        match command {
+            Command::Start => return self.start_test(),
+            Command::Repeat => return self.repeat_test(),
+            Command::OpenSettings => return self.open_settings(),
+            Command::OpenStats => return self.open_stats(),
+            Command::Home => {
+                self.session = None;
+                self.settings = None;
+                self.stats = None;
+                self.screen = Screen::Home;
+                return;
+            }
+            Command::Quit => {
+                self.should_quit = true;
+                return;
+            }
+            Command::ExportHistory => {
+                if !self.persist {
+                    self.notice = Some("history is not saved in this run".to_string());
+                    return;
+                }
+                self.notice = Some(match Data::export_history() {
+                    Ok(path) => format!("history exported to {}", path.display()),
+                    Err(e) => format!("export failed: {e}"),
+                });
+                return;
+            }
+            Command::SetProfile(profile) => {
+                self.switch_profile(profile);
+                if self.screen == Screen::Typing {
+                    self.start_test();
+                }
+                return;
+            }
+            Command::SetTime(time) => {
+                self.time = time;
+                self.refresh_record();
+            }
+            Command::SetTheme(name) => {
+                self.theme = theme::load(&name);
+                self.theme_name = name;
+            }
+            Command::SetLanguage(language) => self.language = language,
+            Command::SetTopWords(top_words) => self.top_words = top_words,
+            Command::SetLazy(lazy) => self.lazy = lazy,
+            Command::SetTextStyle(style) => self.text_style = style,
+            Command::SetMode(tokens) => self.mode_tokens = tokens,
+            Command::SetCursor(cursor) => self.cursor_style = cursor,
+        }
+
+        self.persist();
+        match self.screen {
+            Screen::Typing => self.start_test(),
+            Screen::Settings => self.open_settings(),
+            _ => {}
+        }
     }
 
     fn finish_test(&mut self) {
-        if let Some(session) = self.session.as_ref() {
+        if let Some(session) = self.session.as_ref().filter(|_| self.persist) {
             let wpm = session.stats.wpm() as u32;
             self.previous_record = Data::get_record(self.time).unwrap_or(0);
             self.new_record = wpm > self.previous_record;
 
-            let score = Score::new(wpm, session.stats.raw_wpm() as u32, session.stats.accuracy() as f32);
+            let score = Score::new(wpm, session.stats.raw_wpm() as u32, session.stats.accuracy() as f32)
+                .with_digit_accuracy(session.stats.digit_accuracy())
+                .with_profile(self.config.profile.clone());
             let _ = Data::save_data(score, self.time);
             self.refresh_record();
         }
         self.screen = Screen::Results;
-        self.results_opened = Some(Instant::now());
+        self.results_opened = Some(self.clock.now());
+    }
+
+    pub fn context(&self) -> Context {
+        if self.quick {
+            return Context::Quick;
+        }
+        match self.screen {
+            Screen::Home => Context::Home,
+            Screen::Typing => Context::Typing,
+            Screen::Results => Context::Results,
+            Screen::Settings => Context::Settings,
+            Screen::Stats => Context::Stats,
+        }
     }
 
-    fn handle_event(&mut self, event: Event) {
+    pub(crate) fn handle_event(&mut self, event: Event) {
+        match event {
+            Event::FocusLost if self.screen == Screen::Typing => {
+                if let Some(session) = self.session.as_mut().filter(|s| !s.is_paused()) {
+                    session.pause();
+                    self.focus_paused = session.is_paused();
+                }
+            }
+            Event::FocusGained if std::mem::take(&mut self.focus_paused) => {
+                if let Some(session) = self.session.as_mut() {
+                    session.resume();
+                }
+            }
+            // a keystroke resumes the test by itself
+            Event::Key(_) => self.focus_paused = false,
+            _ => {}
+        }
         if let Event::Key(key) = event {
-            if key.kind != KeyEventKind::Press {
+            // Only terminals with the kitty protocol report releases; a held
+            // key repeats like it would in a text field.
+            if key.kind == KeyEventKind::Release {
                 return;
             }
             if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
                 self.should_quit = true;
                 return;
             }
+            self.notice = None;
+            if self.palette.is_some() {
+                self.handle_palette_key(key);
+                return;
+            }
+            if self.help {
+                self.help = false;
+                return;
+            }
+            if self.settings.as_ref().is_some_and(|s| s.input.is_some()) {
+                self.handle_time_input(key);
+                return;
+            }
+
+            let action = self.config.keymap.action(self.context(), &key);
+            match action {
+                Some(Action::Help) => {
+                    self.help = true;
+                    return;
+                }
+                Some(Action::Palette) => {
+                    let ranked = self.ranked();
+                    self.palette = Some(PaletteState::new(&self.config.profiles, ranked));
+                    return;
+                }
+                _ => {}
+            }
+            if self.quick {
+                self.handle_quick_key(key, action);
+                return;
+            }
             match self.screen {
-                Screen::Home => self.handle_home_key(key),
-                Screen::Typing => self.handle_typing_key(key),
-                Screen::Results => self.handle_results_key(key),
-                Screen::Settings => self.handle_settings_key(key),
-                Screen::Stats => self.handle_stats_key(key),
+                Screen::Home => self.handle_home_key(action),
+                Screen::Typing => self.handle_typing_key(key, action),
+                Screen::Results => self.handle_results_key(action),
+                Screen::Settings => self.handle_settings_key(action),
+                Screen::Stats => self.handle_stats_key(action),
             }
         }
     }
 
-    fn handle_home_key(&mut self, key: KeyEvent) {
+    fn handle_palette_key(&mut self, key: KeyEvent) {
+        let Some(palette) = self.palette.as_mut() else {
+            return;
+        };
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
         match key.code {
-            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
-            KeyCode::Char('s') => self.open_settings(),
-            KeyCode::Char('p') => self.open_stats(),
+            KeyCode::Esc => self.palette = None,
+            KeyCode::Enter => {
+                let command = palette.selected();
+                self.palette = None;
+                if let Some(command) = command {
+                    self.run_command(command);
+                }
+            }
+            KeyCode::Up => palette.move_up(),
+            KeyCode::Down | KeyCode::Tab => palette.move_down(),
+            KeyCode::Char('p') if ctrl => palette.move_up(),
+            KeyCode::Char('n') if ctrl => palette.move_down(),
+            KeyCode::Backspace => palette.pop(),
+            KeyCode::Char(c) if !ctrl => palette.push(c),
+            _ => {}
+        }
+    }
+
+    /// Keys while a custom duration is typed on the settings page.
+    fn handle_time_input(&mut self, key: KeyEvent) {
+        let Some(settings) = self.settings.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Enter if settings.submit_input() => self.apply_settings(),
+            KeyCode::Esc => settings.cancel_input(),
+            KeyCode::Backspace => settings.pop_input(),
+            KeyCode::Char(c) => settings.push_input(c),
+            _ => {}
+        }
+    }
+
+    fn handle_home_key(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Quit) => self.should_quit = true,
+            Some(Action::Settings) => self.open_settings(),
+            Some(Action::Stats) => self.open_stats(),
+            Some(Action::Quick) => self.open_quick(),
             _ => self.start_test(),
         }
     }
 
-    fn handle_stats_key(&mut self, key: KeyEvent) {
-        if matches!(key.code, KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('p')) {
-            self.stats = None;
-            self.screen = Screen::Home;
+    /// Opens the quick menu; a running test waits until it closes.
+    fn open_quick(&mut self) {
+        self.quick = true;
+        if let Some(session) = self.session.as_mut().filter(|s| !s.is_paused()) {
+            session.pause();
+            self.quick_paused = session.is_paused();
+        }
+    }
+
+    fn handle_quick_key(&mut self, key: KeyEvent, action: Option<Action>) {
+        self.quick = false;
+        let paused = std::mem::take(&mut self.quick_paused);
+        match action {
+            Some(Action::Restart) => return self.start_test(),
+            Some(Action::Repeat) => return self.repeat_test(),
+            Some(Action::Quick) | Some(Action::Back) => {
+                if paused {
+                    if let Some(session) = self.session.as_mut() {
+                        session.resume();
+                    }
+                }
+                return;
+            }
+            _ => {}
+        }
+
+        let c = match key.code {
+            KeyCode::Char(c) if QUICK_DIGITS.contains(&c) => c,
+            _ => {
+                self.quick = true;
+                self.quick_paused = paused;
+                return;
+            }
+        };
+        let index = c as usize - *QUICK_DIGITS.start() as usize;
+        let modes = mode_options();
+        if let Some(&time) = TIME_PRESETS.get(index) {
+            self.time = time;
+            self.refresh_record();
+        } else if let Some((_, tokens)) = modes.get(index - TIME_PRESETS.len()) {
+            self.mode_tokens = tokens.iter().map(|t| t.to_string()).collect();
+        } else {
+            self.quick = true;
+            self.quick_paused = paused;
+            return;
+        }
+        if self.screen == Screen::Typing {
+            self.start_test();
         }
     }
 
-    fn handle_typing_key(&mut self, key: KeyEvent) {
+    fn handle_stats_key(&mut self, action: Option<Action>) {
+        match action {
+            Some(Action::Back) | Some(Action::Stats) => {
+                self.stats = None;
+                self.screen = Screen::Home;
+            }
+            Some(Action::Filter) => {
+                if let Some(stats) = self.stats.as_mut() {
+                    stats.next_filter();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_typing_key(&mut self, key: KeyEvent, action: Option<Action>) {
         let Some(session) = self.session.as_mut() else {
             self.screen = Screen::Home;
             return;
         };
-        match key.code {
-            KeyCode::Esc => {
+        match action {
+            Some(Action::Back) => {
                 if self.direct {
                     self.should_quit = true;
                 } else {
@@ -222,9 +819,16 @@ impl App {
                 }
                 return;
             }
+            Some(Action::Quick) => {
+                self.open_quick();
+                return;
+            }
+            _ => {}
+        }
+        match key.code {
             KeyCode::Backspace => session.backspace(),
             KeyCode::Char(' ') => session.space(),
-            KeyCode::Char(c) => session.type_char(c),
+            KeyCode::Char(c) if produces_text(&key) => session.type_char(c),
             _ => {}
         }
         if session.is_finished() {
@@ -232,9 +836,9 @@ impl App {
         }
     }
 
-    fn handle_results_key(&mut self, _key: KeyEvent) {
+    fn handle_results_key(&mut self, action: Option<Action>) {
         if let Some(opened) = self.results_opened {
-            if opened.elapsed() < Duration::from_millis(600) {
+            if self.clock.now().duration_since(opened) < Duration::from_millis(600) {
                 return;
             }
         }
@@ -243,9 +847,10 @@ impl App {
             self.should_quit = true;
             return;
         }
-        match _key.code {
-            KeyCode::Enter => self.start_test(),
-            KeyCode::Char('q') | KeyCode::Esc => {
+        match action {
+            Some(Action::Restart) => self.start_test(),
+            Some(Action::Repeat) => self.repeat_test(),
+            Some(Action::Back) => {
                 self.session = None;
                 self.screen = Screen::Home;
             }
@@ -253,37 +858,38 @@ impl App {
         }
     }
 
-    fn handle_settings_key(&mut self, key: KeyEvent) {
+    fn handle_settings_key(&mut self, action: Option<Action>) {
         let post = {
             let Some(st) = self.settings.as_mut() else {
                 self.screen = Screen::Home;
                 return;
             };
-            match key.code {
-                KeyCode::Char('j') | KeyCode::Down => {
+            match action {
+                Some(Action::Down) => {
                     st.move_down();
                     Post::None
                 }
-                KeyCode::Char('k') | KeyCode::Up => {
+                Some(Action::Up) => {
                     st.move_up();
                     Post::None
                 }
-                KeyCode::Enter | KeyCode::Char('l') | KeyCode::Char(' ') => {
-                    if st.open {
-                        st.confirm();
+                Some(Action::Select) => {
+                    if !st.open {
+                        st.open();
+                        Post::None
+                    } else if st.confirm() {
                         Post::Apply
                     } else {
-                        st.open();
                         Post::None
                     }
                 }
-                KeyCode::Char('h') => {
+                Some(Action::Close) => {
                     if st.open {
                         st.close();
                     }
                     Post::None
                 }
-                KeyCode::Esc | KeyCode::Char('q') => {
+                Some(Action::Back) => {
                     if st.open {
                         st.close();
                         Post::None
@@ -333,25 +939,26 @@ fn cursor_shape(name: &str) -> SetCursorStyle {
     }
 }
 
-pub fn run(
-    theme: Theme,
-    cursor_style: String,
-    language: String,
-    mode_tokens: Vec<String>,
-    time: u64,
-    direct: bool,
-) -> Result<()> {
+/// Whether a key press types its char: plain or shifted, or with AltGr,
+/// which some terminals report as Ctrl+Alt.
+fn produces_text(key: &KeyEvent) -> bool {
+    let chord = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+    chord.is_empty() || chord == KeyModifiers::CONTROL | KeyModifiers::ALT
+}
+
+pub fn run(config: AppConfig) -> Result<()> {
     let mut tui = Tui::new()?;
     tui.enter()?;
 
-    let mut app = App::new(theme, cursor_style, language, mode_tokens, time, direct);
-    if direct {
+    let mut app = App::new(config);
+    if app.direct {
         app.start_test();
     }
 
     let result = (|| -> Result<()> {
         while !app.should_quit {
             app.tick();
+            app.watch_config();
             if app.screen == Screen::Typing {
                 let _ = execute!(stdout(), cursor_shape(&app.cursor_style));
             }
@@ -364,5 +971,344 @@ pub fn run(
     })();
 
     tui.exit()?;
+    // a direct run that ended without a test, e.g. invalidated while AFK,
+    // says why once the screen is gone
+    if app.direct && app.session.is_none() {
+        if let Some(notice) = &app.notice {
+            eprintln!("{notice}");
+        }
+    }
     result
 }
+
+#[cfg(test)]
+mod app_tests {
+    use super::*;
+    use crate::clock::FakeClock;
+    use crate::config::afk::AfkSettings;
+    use crossterm::event::{KeyEventState, ModifierKeyCode};
+
+    /// A test of `text`, or of random German words if there is none.
+    fn test_app(text: Option<&str>) -> App {
+        crate::paths::init_for_tests();
+        let clock = FakeClock::new();
+        let mut app = App::new(AppConfig {
+            language: "german".to_string(),
+            top_words: 0,
+            lazy: false,
+            text_style: TextStyle::Words,
+            filter: WordFilter::default(),
+            mode_tokens: vec!["normal".to_string()],
+            time: 30,
+            direct: false,
+            text: text.map(str::to_string),
+            persist: false,
+            clock: clock.shared(),
+            notice: None,
+            settings: Settings::default(),
+        });
+        app.start_test();
+        app
+    }
+
+    fn typing(text: &str) -> App {
+        test_app(Some(text))
+    }
+
+    fn key(code: KeyCode, modifiers: KeyModifiers, kind: KeyEventKind) -> Event {
+        Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind,
+            state: KeyEventState::NONE,
+        })
+    }
+
+    fn press(app: &mut App, code: KeyCode, modifiers: KeyModifiers) {
+        app.handle_event(key(code, modifiers, KeyEventKind::Press));
+    }
+
+    fn type_chars(app: &mut App, text: &str) {
+        for c in text.chars() {
+            press(app, KeyCode::Char(c), KeyModifiers::NONE);
+        }
+    }
+
+    fn typed(app: &App, word: usize) -> String {
+        app.session.as_ref().unwrap().words[word]
+            .typed
+            .iter()
+            .collect()
+    }
+
+    fn targets(app: &App) -> Vec<Vec<char>> {
+        let words = &app.session.as_ref().unwrap().words;
+        words.iter().map(|w| w.target.clone()).collect()
+    }
+
+    #[test]
+    fn test_composed_and_decomposed_input() {
+        let mut app = typing("schön café ok");
+        type_chars(&mut app, "schön cafe\u{301} ");
+        assert_eq!(typed(&app, 0), "schön");
+        assert_eq!(typed(&app, 1), "café");
+        type_chars(&mut app, "ok");
+        assert_eq!(app.screen, Screen::Results);
+        assert_eq!(app.session.as_ref().unwrap().stats.accuracy(), 100.0);
+    }
+
+    #[test]
+    fn test_dead_key_sequences() {
+        let mut app = typing("für être mâine ok");
+        type_chars(&mut app, "f¨ur ^etre m^aine");
+        assert_eq!(typed(&app, 0), "für");
+        assert_eq!(typed(&app, 1), "être");
+        assert_eq!(typed(&app, 2), "mâine");
+
+        // a dead key with nothing to accent types the accent itself
+        let mut app = typing("ab cd");
+        type_chars(&mut app, "a´ ");
+        assert_eq!(typed(&app, 0), "a´");
+        type_chars(&mut app, "´x");
+        assert_eq!(typed(&app, 1), "´x");
+        // ASCII accents only wait for a letter the word puts that accent on
+        let mut app = typing("a~b ~ño");
+        type_chars(&mut app, "a~");
+        assert_eq!(typed(&app, 0), "a~");
+        type_chars(&mut app, "^");
+        assert_eq!(typed(&app, 0), "a~^");
+        type_chars(&mut app, " ~~n");
+        assert_eq!(typed(&app, 1), "~ñ");
+        // backspace drops a pending dead key
+        let mut app = typing("ab cd");
+        type_chars(&mut app, "a¨");
+        press(&mut app, KeyCode::Backspace, KeyModifiers::NONE);
+        type_chars(&mut app, "b");
+        assert_eq!(typed(&app, 0), "ab");
+    }
+
+    #[test]
+    fn test_cyrillic_input() {
+        // some terminals send й as и followed by a combining breve
+        let mut app = typing("мой мир");
+        type_chars(&mut app, "мои\u{306} ми");
+        assert_eq!(typed(&app, 0), "мой");
+        assert_eq!(typed(&app, 1), "ми");
+        assert_eq!(app.session.as_ref().unwrap().stats.letter_count, 5);
+    }
+
+    #[test]
+    fn test_kitty_event_kinds_and_modifiers() {
+        let mut app = typing("Aaa@ b");
+        // shifted and AltGr (Ctrl+Alt) keys type, releases are ignored
+        press(&mut app, KeyCode::Char('A'), KeyModifiers::SHIFT);
+        app.handle_event(key(
+            KeyCode::Char('A'),
+            KeyModifiers::SHIFT,
+            KeyEventKind::Release,
+        ));
+        press(&mut app, KeyCode::Char('a'), KeyModifiers::NONE);
+        app.handle_event(key(
+            KeyCode::Char('a'),
+            KeyModifiers::NONE,
+            KeyEventKind::Repeat,
+        ));
+        press(
+            &mut app,
+            KeyCode::Char('@'),
+            KeyModifiers::CONTROL | KeyModifiers::ALT,
+        );
+        assert_eq!(typed(&app, 0), "Aaa@");
+
+        // Ctrl or Alt chords never end up in the text
+        press(&mut app, KeyCode::Char('w'), KeyModifiers::CONTROL);
+        press(&mut app, KeyCode::Char('b'), KeyModifiers::ALT);
+        press(
+            &mut app,
+            KeyCode::Modifier(ModifierKeyCode::LeftShift),
+            KeyModifiers::SHIFT,
+        );
+        assert_eq!(typed(&app, 0), "Aaa@");
+        assert_eq!(app.screen, Screen::Typing);
+    }
+
+    #[test]
+    fn test_custom_time_in_settings() {
+        let mut app = typing("ok");
+        app.session = None;
+        app.open_settings();
+        for _ in 0..10 {
+            press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+        }
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        for _ in 0..10 {
+            press(&mut app, KeyCode::Down, KeyModifiers::NONE);
+        }
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        type_chars(&mut app, "q90");
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert_eq!(app.time, 90);
+        assert_eq!(app.screen, Screen::Settings);
+        assert!(!app.settings.as_ref().unwrap().open);
+    }
+
+    #[test]
+    fn test_apply_config() {
+        let mut app = typing("ok");
+        app.session = None;
+        app.screen = Screen::Home;
+        let text = "cursor = \"bar\"\n[game]\ntime = 60\n[modes]\ndefault_mode = \"numbers\"\n\
+                    [keybindings]\nsettings = \"o\"\n";
+        app.apply_config(Settings::parse(text, Profile::Default).unwrap());
+        assert_eq!(app.cursor_style, "bar");
+        assert_eq!(app.time, 60);
+        assert_eq!(app.mode_tokens, vec!["numbers"]);
+
+        press(&mut app, KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(app.screen, Screen::Settings);
+    }
+
+    #[test]
+    fn test_reload_only_when_idle() {
+        let mut app = typing("ok");
+        assert!(!app.can_reload());
+        app.session = None;
+        app.screen = Screen::Home;
+        assert!(app.can_reload());
+        app.quick = true;
+        assert!(!app.can_reload());
+        app.quick = false;
+
+        app.open_settings();
+        assert!(app.can_reload());
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(app.settings.as_ref().unwrap().open);
+        assert!(!app.can_reload());
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(app.can_reload());
+    }
+
+    #[test]
+    fn test_afk_invalidate_ends_a_direct_run() {
+        let clock = FakeClock::new();
+        let mut app = typing("hello world");
+        app.clock = clock.shared();
+        app.config.afk = AfkSettings {
+            timeout: 5,
+            action: AfkAction::Invalidate,
+        };
+        app.start_test();
+        type_chars(&mut app, "he");
+        clock.advance_secs(6.0);
+        app.tick();
+        assert_eq!((app.screen, app.should_quit), (Screen::Home, false));
+
+        app.direct = true;
+        app.start_test();
+        type_chars(&mut app, "he");
+        clock.advance_secs(6.0);
+        app.tick();
+        assert!(app.should_quit);
+        assert!(app.session.is_none());
+    }
+
+    #[test]
+    fn test_export_needs_persist() {
+        let mut app = test_app(None);
+        app.run_command(Command::ExportHistory);
+        let notice = app.notice.as_deref();
+        assert_eq!(notice, Some("history is not saved in this run"));
+    }
+
+    #[test]
+    fn test_stats_profile_filter() {
+        let score = |wpm, profile: Option<&str>| {
+            Score::new(wpm, wpm, 100.0).with_profile(profile.map(str::to_string))
+        };
+        let scores = vec![
+            score(80, Some("warmup")),
+            score(40, None),
+            score(60, Some("code")),
+            score(70, Some("code")),
+        ];
+        let mut stats = StatsData::new(Averages::of(&scores), scores);
+        assert!(stats.has_profiles());
+        assert_eq!(stats.visible().len(), 4);
+        assert_eq!(stats.filter_label(), "all profiles");
+
+        let mut seen = Vec::new();
+        for _ in 0..4 {
+            stats.next_filter();
+            let wpm: Vec<u32> = stats.visible().iter().map(|s| s.wpm).collect();
+            seen.push((stats.filter_label().to_string(), wpm));
+        }
+        assert_eq!(
+            seen,
+            vec![
+                ("no profile".to_string(), vec![40]),
+                ("code".to_string(), vec![60, 70]),
+                ("warmup".to_string(), vec![80]),
+                ("all profiles".to_string(), vec![80, 40, 60, 70]),
+            ]
+        );
+        stats.next_filter();
+        stats.next_filter();
+        assert_eq!(stats.visible_averages().wpm_avg.avg, 65.0);
+    }
+
+    #[test]
+    fn test_quick_repeat_and_restart() {
+        let mut app = test_app(None);
+        let words = targets(&app);
+        type_chars(&mut app, "xy");
+
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        type_chars(&mut app, "r");
+        assert!(!app.quick);
+        assert_eq!(app.screen, Screen::Typing);
+        assert_eq!(targets(&app), words);
+        assert_eq!(typed(&app, 0), "");
+
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        press(&mut app, KeyCode::Enter, KeyModifiers::NONE);
+        assert!(!app.quick);
+        assert_ne!(targets(&app), words);
+    }
+
+    #[test]
+    fn test_quick_time_and_mode() {
+        let mut app = test_app(None);
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        type_chars(&mut app, "3");
+        assert!(!app.quick);
+        assert_eq!(app.time, 60);
+        assert_eq!(app.session.as_ref().unwrap().duration, 60);
+
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        type_chars(&mut app, "9");
+        assert_eq!(app.mode_tokens, vec!["uppercase", "punctuation"]);
+        assert_eq!(app.time, 60);
+
+        // other keys leave the menu open and change nothing
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        type_chars(&mut app, "x0");
+        assert!(app.quick);
+        press(&mut app, KeyCode::Esc, KeyModifiers::NONE);
+        assert!(!app.quick);
+        assert_eq!(app.time, 60);
+
+        // on the home screen the choice waits for the next test
+        app.session = None;
+        app.screen = Screen::Home;
+        press(&mut app, KeyCode::Tab, KeyModifiers::NONE);
+        type_chars(&mut app, "5");
+        assert_eq!(app.mode_tokens, vec!["normal"]);
+        assert_eq!(app.screen, Screen::Home);
+        assert!(app.session.is_none());
+    }
+
+    #[test]
+    fn test_quick_options_fit_the_digit_keys() {
+        assert!(TIME_PRESETS.len() + mode_options().len() <= QUICK_DIGITS.count());
+    }
+}
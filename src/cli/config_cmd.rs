@@ -0,0 +1,81 @@
+use anyhow::{bail, Result};
+
+use super::parser::ConfigCommand;
+use crate::config::{self, check, ConfigValue};
+use crate::paths;
+
+pub(crate) fn run(command: ConfigCommand) -> Result<()> {
+    match command {
+        ConfigCommand::Check => run_check(),
+        ConfigCommand::Get { key } => {
+            match config::get_value(&key)? {
+                ConfigValue::Set(value) | ConfigValue::Default(value) => println!("{value}"),
+                ConfigValue::Unset => bail!("`{key}` is not set"),
+            }
+            Ok(())
+        }
+        ConfigCommand::Set { key, value } => {
+            config::set_value(&key, &value)?;
+            println!("{key} = {value}");
+            Ok(())
+        }
+        ConfigCommand::Unset { key } => {
+            if config::unset_value(&key)? {
+                println!("Unset {key}");
+            } else {
+                println!("{key} was not set");
+            }
+            Ok(())
+        }
+        ConfigCommand::Reset => {
+            match config::reset_config()? {
+                Some(backup) => {
+                    println!("Reset the config; the old one is at {}", backup.display())
+                }
+                None => println!("Created an empty config"),
+            }
+            Ok(())
+        }
+        ConfigCommand::Path => {
+            println!("{}", config::config_path()?.display());
+            Ok(())
+        }
+        ConfigCommand::Migrate => {
+            let changes = config::migrate_config()?;
+            if changes.is_empty() {
+                println!("The config file is up to date");
+            }
+            for change in changes {
+                println!("{change}");
+            }
+            Ok(())
+        }
+    }
+}
+
+fn run_check() -> Result<()> {
+    let Some(report) = check::check_config()? else {
+        match paths::config_file() {
+            Some(path) => println!("No config file at {}; using the defaults", path.display()),
+            None => println!("No config file; using the defaults"),
+        }
+        return Ok(());
+    };
+
+    for line in report.lines() {
+        println!("{line}");
+    }
+    let (errors, warnings) = (report.errors(), report.warnings());
+    if errors == 0 && warnings == 0 {
+        println!("{}: no problems found", report.path.display());
+        return Ok(());
+    }
+    if report.ignored {
+        println!("The file can't be loaded, so typy runs on its defaults.");
+    }
+    if errors > 0 {
+        bail!("{errors} error(s) and {warnings} warning(s) in the config");
+    }
+    println!("{warnings} warning(s)");
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use comfy_table::presets::UTF8_FULL;
+use comfy_table::{Attribute, Cell, ContentArrangement, Table};
+
+use super::parser::LangCommand;
+use crate::config::Settings;
+use crate::word_provider::{PackInfo, Packs};
+
+pub(crate) fn run(command: LangCommand, settings: &Settings) -> Result<()> {
+    let packs = Packs::user()?;
+    match command {
+        LangCommand::List => ignore_broken_pipe(print_list(&packs.list()?))?,
+        LangCommand::Info { language } => ignore_broken_pipe(print_info(&packs.info(&language)?))?,
+        LangCommand::Install {
+            sources,
+            file,
+            mirror,
+        } => {
+            let mirror = mirror.unwrap_or_else(|| settings.language.mirror.clone());
+            for source in sources {
+                let info = if file {
+                    packs.install_file(Path::new(&source))
+                } else {
+                    packs.install(&source, &mirror)
+                }
+                .with_context(|| format!("Failed to install `{source}`"))?;
+                println!(
+                    "Installed {} ({} words) from {}",
+                    info.name, info.words, info.source
+                );
+            }
+        }
+        LangCommand::Remove { languages } => {
+            for language in languages {
+                packs.remove(&language)?;
+                println!("Removed {language}");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Output piped into e.g. `head` may stop being read early, which is fine.
+fn ignore_broken_pipe(result: io::Result<()>) -> Result<()> {
+    match result {
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        result => Ok(result?),
+    }
+}
+
+fn print_list(packs: &[PackInfo]) -> io::Result<()> {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            ["Language", "Source", "Words", "Checksum"]
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold)),
+        );
+    for pack in packs {
+        let source = match (&pack.path, pack.bundled) {
+            (None, _) => "bundled".to_string(),
+            (Some(_), true) => format!("installed, overrides bundled ({})", pack.source),
+            (Some(_), false) => format!("installed ({})", pack.source),
+        };
+        table.add_row(vec![
+            pack.name.clone(),
+            source,
+            pack.words.to_string(),
+            status(pack).to_string(),
+        ]);
+    }
+    writeln!(io::stdout().lock(), "{table}")
+}
+
+fn print_info(pack: &PackInfo) -> io::Result<()> {
+    let mut out = io::stdout().lock();
+    writeln!(out, "language: {}", pack.name)?;
+    writeln!(out, "source:   {}", pack.source)?;
+    if let Some(path) = &pack.path {
+        writeln!(out, "path:     {}", path.display())?;
+    }
+    writeln!(out, "bundled:  {}", if pack.bundled { "yes" } else { "no" })?;
+    writeln!(out, "words:    {}", pack.words)?;
+    writeln!(out, "sha256:   {}", pack.sha256)?;
+    writeln!(out, "checksum: {}", status(pack))
+}
+
+fn status(pack: &PackInfo) -> &'static str {
+    match pack.verified {
+        Some(true) => "ok",
+        Some(false) => "MISMATCH",
+        None => "unknown",
+    }
+}
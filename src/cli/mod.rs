@@ -1,19 +1,37 @@
+mod config_cmd;
+mod lang;
 mod parser;
 
 use anyhow::{Context, Result};
 use clap::Parser;
-use parser::Cli;
+use parser::{Cli, Command, FilterArgs, SimulateArgs};
 
 use crate::app;
+use crate::clock::SystemClock;
 use crate::config;
+use crate::config::toml_parser::{ConfigToml, Profile};
+use crate::config::Settings;
 use crate::mode::Mode;
+use crate::paths;
 use crate::scores::progress::display;
-use crate::theme;
+use crate::simulate::{self, Simulation};
+use crate::word_provider::{MarkovText, TextStyle, WordFilter, WordList};
 
 pub fn run() -> Result<()> {
     let cli = Cli::parse();
+    paths::init(cli.config.clone(), cli.data_dir.clone());
 
-    if cli.config {
+    match cli.command {
+        Some(Command::Simulate(args)) => return run_simulation(args),
+        Some(Command::Lang(command)) => {
+            let settings = Settings::from_toml(load_config(cli.profile.as_deref())?)?;
+            return lang::run(command, &settings);
+        }
+        Some(Command::Config(command)) => return config_cmd::run(command),
+        None => {}
+    }
+
+    if cli.edit_config {
         config::create_config()?;
         config::open_config()?;
         return Ok(());
@@ -24,42 +42,120 @@ pub fn run() -> Result<()> {
         return Ok(());
     }
 
-    let language = config::language::Language::new().lang;
-
-    let theme_name = config::toml_parser::get_config()
-        .lock()
-        .ok()
-        .and_then(|c| c.get_theme())
-        .unwrap_or_else(|| theme::DEFAULT_THEME.to_string());
-    let theme = theme::load(&theme_name);
-
-    let cursor_style = config::toml_parser::get_config()
-        .lock()
-        .ok()
-        .and_then(|c| c.get_cursor())
-        .unwrap_or_else(|| "block".to_string());
-
-    let config_time = config::toml_parser::get_config()
-        .lock()
-        .ok()
-        .and_then(|c| c.get_game())
-        .and_then(|g| g.time);
-    let time = cli.time.or(config_time).unwrap_or(30);
+    let config = load_config(cli.profile.as_deref())?;
+    let notice = config_notice();
+    let settings = Settings::from_toml(config)?;
+    let language = settings.language.clone();
+    let time = cli.time.unwrap_or(settings.time.get());
 
     let mode_tokens: Vec<String> = if !cli.mode.is_empty() {
         cli.mode.clone()
     } else {
-        config::mode_settings::ModeSettings::new()
-            .default_modes
-            .iter()
-            .map(|m| m.token().to_string())
-            .collect()
+        settings.mode_tokens()
     };
 
     Mode::from_str(mode_tokens.iter().map(|s| s.as_str()).collect())
         .context("Failed to parse mode")?;
 
+    let filter =
+        word_filter(settings.filter.clone(), cli.filters).context("Invalid word filters")?;
+    WordList::load(&language.lang)
+        .and_then(|list| filter.apply(list))
+        .with_context(|| format!("Cannot use the `{}` word list", language.lang))?;
+
+    let text_style = match cli.text.as_deref() {
+        Some(style) => style.parse()?,
+        None => language.text_style,
+    };
+    if text_style == TextStyle::Sentences {
+        MarkovText::load(&language.lang)
+            .with_context(|| format!("Cannot generate `{}` sentences", language.lang))?;
+    }
+
     let direct = cli.time.is_some() || !cli.mode.is_empty();
 
-    app::run(theme, cursor_style, language, mode_tokens, time, direct)
+    app::run(app::AppConfig {
+        language: language.lang,
+        top_words: language.top_words,
+        lazy: cli.lazy || language.lazy,
+        text_style,
+        filter,
+        mode_tokens,
+        time,
+        direct,
+        text: None,
+        persist: true,
+        clock: SystemClock::shared(),
+        notice,
+        settings,
+    })
+}
+
+/// The config file with `--profile` applied. A broken file only fails
+/// with a profile; otherwise `config_notice` reports it.
+fn load_config(profile: Option<&str>) -> Result<ConfigToml> {
+    Ok(match profile {
+        Some(profile) => ConfigToml::load(Profile::Named(profile))?,
+        None => ConfigToml::new(),
+    })
+}
+
+/// Prints problems in the config file and sums them up for the home screen.
+fn config_notice() -> Option<String> {
+    let report = match config::check::check_config() {
+        Ok(report) => report?,
+        Err(e) => return Some(format!("{e:#}")),
+    };
+    for line in report.lines() {
+        eprintln!("{line}");
+    }
+    report.banner()
+}
+
+/// The config's word filter with the filter flags on top.
+fn word_filter(mut filter: WordFilter, args: FilterArgs) -> Result<WordFilter> {
+    if args.min_length.is_some() {
+        filter.min_length = args.min_length;
+    }
+    if args.max_length.is_some() {
+        filter.max_length = args.max_length;
+    }
+    if args.allowed_chars.is_some() {
+        filter.allowed_chars = args.allowed_chars;
+    }
+    if args.required_chars.is_some() {
+        filter.required_chars = args.required_chars;
+    }
+    match args.blocklist {
+        Some(path) => filter.with_blocklist_file(&path),
+        None => Ok(filter),
+    }
+}
+
+fn run_simulation(args: SimulateArgs) -> Result<()> {
+    let script = std::fs::read_to_string(&args.script)
+        .with_context(|| format!("Failed to read script {}", args.script.display()))?;
+    let steps = simulate::parse_script(&script).context("Failed to parse script")?;
+
+    let mode_tokens = if args.mode.is_empty() {
+        vec!["normal".to_string()]
+    } else {
+        args.mode
+    };
+    Mode::from_str(mode_tokens.iter().map(|s| s.as_str()).collect())
+        .context("Failed to parse mode")?;
+
+    let mut simulation = Simulation::new(simulate::Options {
+        text: args.text,
+        language: args.language,
+        time: args.time,
+        mode_tokens,
+        width: args.width,
+        height: args.height,
+    })?;
+    simulation.run(&steps)?;
+
+    let outcome = simulation.outcome(args.frame);
+    println!("{}", serde_json::to_string_pretty(&outcome)?);
+    Ok(())
 }
@@ -1,4 +1,8 @@
-use clap::Parser;
+use std::path::PathBuf;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::model::Seconds;
 
 #[derive(Parser)]
 #[command(name = "typy")]
@@ -13,15 +17,24 @@ USAGE MODES:
   • With mode:     typy -m punctuation      - Start a test with the given mode(s)
   • Stats:         typy -s                  - Show statistics for your past games
   • Config:        typy -c                  - Create and open the config file
+  • Config file:   typy --config FILE       - Use another config file (or set TYPY_CONFIG)
+  • Profile:       typy --profile NAME      - Use the settings of [profiles.NAME]
+  • Languages:     typy lang list           - Show, install and remove word lists
+  • Check config:  typy config check        - Report problems in the config file
+  • Change config: typy config set game.time 60
 
 For more information check: https://github.com/Pazl27/typy-cli")]
 pub(crate) struct Cli {
+    #[command(subcommand)]
+    pub(crate) command: Option<Command>,
+
     #[arg(
         short = 't',
         long = "time",
         value_name = "SECONDS",
+        value_parser = seconds,
         help_heading = "Game options",
-        help = "Duration of the test in seconds.\nStarts a test immediately and exits when it finishes."
+        help = "Duration of the test, e.g. 90 or 2m.\nStarts a test immediately and exits when it finishes."
     )]
     pub(crate) time: Option<u64>,
 
@@ -31,10 +44,37 @@ pub(crate) struct Cli {
         value_name = "MODE",
         num_args = 1..,
         help_heading = "Game options",
-        help = "Mode(s) to play: normal, uppercase, punctuation.\nStarts a test immediately."
+        help = "Mode(s) to play: normal, uppercase, punctuation, numbers.\nStarts a test immediately."
     )]
     pub(crate) mode: Vec<String>,
 
+    #[arg(
+        long = "lazy",
+        help_heading = "Game options",
+        help = "Accept letters typed without their accents, e.g. e for é."
+    )]
+    pub(crate) lazy: bool,
+
+    #[arg(
+        long = "text",
+        value_name = "STYLE",
+        value_parser = ["words", "sentences"],
+        help_heading = "Game options",
+        help = "Random words, or sentences generated from the language's corpus."
+    )]
+    pub(crate) text: Option<String>,
+
+    #[arg(
+        long = "profile",
+        value_name = "NAME",
+        help_heading = "Game options",
+        help = "Settings from [profiles.NAME] in the config, on top of the rest.\nOverrides the `profile` key."
+    )]
+    pub(crate) profile: Option<String>,
+
+    #[command(flatten)]
+    pub(crate) filters: FilterArgs,
+
     #[arg(
         short = 's',
         long = "stats",
@@ -45,9 +85,189 @@ pub(crate) struct Cli {
 
     #[arg(
         short = 'c',
-        long = "config",
+        long = "edit-config",
         help_heading = "Utility",
         help = "Create the config file if missing and open it in $EDITOR."
     )]
-    pub(crate) config: bool,
+    pub(crate) edit_config: bool,
+
+    #[arg(
+        long = "config",
+        value_name = "FILE",
+        global = true,
+        help_heading = "Paths",
+        help = "Config file to use instead of $XDG_CONFIG_HOME/typy/config.toml.\nOverrides TYPY_CONFIG."
+    )]
+    pub(crate) config: Option<PathBuf>,
+
+    #[arg(
+        long = "data-dir",
+        value_name = "DIR",
+        global = true,
+        help_heading = "Paths",
+        help = "Directory for scores and word lists instead of $XDG_DATA_HOME/typy.\nOverrides TYPY_DATA_DIR."
+    )]
+    pub(crate) data_dir: Option<PathBuf>,
+}
+
+/// Word pool filters; each one overrides the same key in `[filters]`.
+#[derive(Args)]
+#[command(next_help_heading = "Word filters")]
+pub(crate) struct FilterArgs {
+    #[arg(long, value_name = "N", help = "Only use words with at least N characters.")]
+    pub(crate) min_length: Option<usize>,
+
+    #[arg(long, value_name = "N", help = "Only use words with at most N characters.")]
+    pub(crate) max_length: Option<usize>,
+
+    #[arg(
+        long,
+        value_name = "CHARS",
+        help = "Only use words made of these characters, e.g. asdfghjkl."
+    )]
+    pub(crate) allowed_chars: Option<String>,
+
+    #[arg(
+        long,
+        value_name = "CHARS",
+        help = "Only use words containing at least one of these characters."
+    )]
+    pub(crate) required_chars: Option<String>,
+
+    #[arg(long, value_name = "FILE", help = "Never use the words in FILE (one per line).")]
+    pub(crate) blocklist: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+pub(crate) enum Command {
+    /// Manage word lists.
+    #[command(subcommand)]
+    Lang(LangCommand),
+
+    /// Inspect and change the config file.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+
+    /// Run the app headless from a script of key presses and print the result as JSON.
+    #[command(hide = true)]
+    Simulate(SimulateArgs),
+}
+
+#[derive(Subcommand)]
+pub(crate) enum LangCommand {
+    /// Show bundled and installed word lists.
+    List,
+
+    /// Show the source, size and checksum of a word list.
+    Info {
+        #[arg(value_name = "LANGUAGE")]
+        language: String,
+    },
+
+    /// Install word lists by name from a mirror, or from local files with --file.
+    Install {
+        #[arg(value_name = "LANGUAGE|FILE", required = true)]
+        sources: Vec<String>,
+
+        #[arg(
+            long,
+            conflicts_with = "mirror",
+            help = "Install the given files instead of downloading by name."
+        )]
+        file: bool,
+
+        #[arg(
+            long,
+            value_name = "URL|DIR",
+            help = "Mirror with a manifest.toml, overriding `mirror` in [language]."
+        )]
+        mirror: Option<String>,
+    },
+
+    /// Remove installed word lists from the data directory.
+    Remove {
+        #[arg(value_name = "LANGUAGE", required = true)]
+        languages: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ConfigCommand {
+    /// Report unknown keys, wrong types and unusable values, with their line and column.
+    Check,
+
+    /// Print the value of a key, or its default if it is not set.
+    Get {
+        #[arg(value_name = "KEY", help = "Dotted key, e.g. theme, game.time or keybindings.quit.")]
+        key: String,
+    },
+
+    /// Set a key, checking the value against the config schema.
+    Set {
+        #[arg(value_name = "KEY")]
+        key: String,
+
+        #[arg(value_name = "VALUE", help = "The value; separate several keys with spaces.")]
+        value: String,
+    },
+
+    /// Remove a key so the default is used.
+    Unset {
+        #[arg(value_name = "KEY")]
+        key: String,
+    },
+
+    /// Replace the config file with an empty one, keeping a .bak copy.
+    Reset,
+
+    /// Print the path of the config file.
+    Path,
+
+    /// Upgrade a config file written by an older typy, listing every change.
+    Migrate,
+}
+
+#[derive(Args)]
+pub(crate) struct SimulateArgs {
+    #[arg(long, value_name = "FILE", help = "Script with one step per line.")]
+    pub(crate) script: PathBuf,
+
+    #[arg(long, value_name = "TEXT", help = "Fixed text to type instead of random words.")]
+    pub(crate) text: Option<String>,
+
+    #[arg(
+        long = "lang",
+        value_name = "LANGUAGE",
+        default_value = "english",
+        help = "Language of the words; also sets the text direction."
+    )]
+    pub(crate) language: String,
+
+    #[arg(
+        short = 't',
+        long = "time",
+        value_name = "SECONDS",
+        value_parser = seconds,
+        default_value = "30"
+    )]
+    pub(crate) time: u64,
+
+    #[arg(short = 'm', long = "mode", value_name = "MODE", num_args = 1..)]
+    pub(crate) mode: Vec<String>,
+
+    #[arg(long, default_value_t = 80)]
+    pub(crate) width: u16,
+
+    #[arg(long, default_value_t = 24)]
+    pub(crate) height: u16,
+
+    #[arg(long, help = "Include the last rendered frame in the output.")]
+    pub(crate) frame: bool,
+}
+
+/// A test duration in seconds, checked against the config model's bounds.
+fn seconds(raw: &str) -> Result<u64, String> {
+    raw.parse::<Seconds>()
+        .map(Seconds::get)
+        .map_err(|e| e.to_string())
 }
@@ -0,0 +1,47 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use super::{Clock, SharedClock};
+
+/// A clock that only moves when told to. Clones share the same time, so a
+/// test can keep one handle and advance the clock it gave to the engine.
+#[derive(Clone)]
+pub struct FakeClock {
+    base: Instant,
+    offset_nanos: Arc<AtomicU64>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        FakeClock {
+            base: Instant::now(),
+            offset_nanos: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn shared(&self) -> SharedClock {
+        Arc::new(self.clone())
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.offset_nanos
+            .fetch_add(by.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    pub fn advance_secs(&self, secs: f64) {
+        self.advance(Duration::from_secs_f64(secs));
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        FakeClock::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.base + Duration::from_nanos(self.offset_nanos.load(Ordering::SeqCst))
+    }
+}
@@ -0,0 +1,51 @@
+mod fake;
+
+use std::sync::Arc;
+use std::time::Instant;
+
+pub use fake::FakeClock;
+
+/// Source of time for the typing engine and the app loop.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+pub type SharedClock = Arc<dyn Clock>;
+
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn shared() -> SharedClock {
+        Arc::new(SystemClock)
+    }
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+#[cfg(test)]
+mod clock_tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_fake_clock_only_moves_when_advanced() {
+        let clock = FakeClock::new();
+        let start = clock.now();
+        assert_eq!(clock.now(), start);
+        clock.advance(Duration::from_millis(1500));
+        assert_eq!(clock.now() - start, Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn test_fake_clock_clones_share_time() {
+        let clock = FakeClock::new();
+        let shared = clock.shared();
+        let start = shared.now();
+        clock.advance_secs(2.0);
+        assert_eq!(shared.now() - start, Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,513 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use toml_edit::{ImDocument, Item, Key, TableLike};
+
+use super::schema::{self, Known, Severity};
+use super::toml_parser::{ConfigToml, Profile, TOP_LEVEL_ONLY};
+use crate::keymap::{Action, KeyChord, Keymap};
+use crate::paths;
+
+/// One problem in the config file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// 1-based line and column of the offending key or value.
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some((line, column)) = self.position {
+            write!(f, "{line}:{column}: ")?;
+        }
+        write!(f, "{}: {}", self.severity.name(), self.message)
+    }
+}
+
+/// The result of checking the config file typy reads.
+pub struct Report {
+    pub path: PathBuf,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Whether the file fails to load, so typy runs on its defaults.
+    pub ignored: bool,
+}
+
+impl Report {
+    pub fn errors(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn warnings(&self) -> usize {
+        self.count(Severity::Warning)
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    }
+
+    /// Each diagnostic prefixed with the file, like compiler output.
+    pub fn lines(&self) -> Vec<String> {
+        self.diagnostics
+            .iter()
+            .map(|d| format!("{}:{d}", self.path.display()))
+            .collect()
+    }
+
+    /// A one-line summary for the home screen banner.
+    pub fn banner(&self) -> Option<String> {
+        let prefix = if self.ignored {
+            "config ignored"
+        } else {
+            "config"
+        };
+        Some(format!("{prefix}: {}", self.summary()?))
+    }
+
+    /// The first problem and how many more there are.
+    pub fn summary(&self) -> Option<String> {
+        let first = self.diagnostics.first()?;
+        let mut summary = String::new();
+        if let Some((line, _)) = first.position {
+            summary.push_str(&format!("line {line}: "));
+        }
+        summary.push_str(&first.message);
+        match self.diagnostics.len() - 1 {
+            0 => summary.push_str(" (see `typy config check`)"),
+            more => summary.push_str(&format!(" (+{more} more, see `typy config check`)")),
+        }
+        Some(summary)
+    }
+}
+
+/// Checks the config file typy reads; `None` when there is none.
+pub fn check_config() -> Result<Option<Report>> {
+    let Some(path) = paths::config_file().filter(|p| p.is_file()) else {
+        return Ok(None);
+    };
+    let text = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read config file {}", path.display()))?;
+    let diagnostics = check_text(&text, &Known::current());
+    let ignored = ConfigToml::parse(&text, Profile::Default).is_err();
+    Ok(Some(Report {
+        path,
+        diagnostics,
+        ignored,
+    }))
+}
+
+/// Reports syntax errors, unknown keys, wrong types and values typy can't
+/// use, in the order they appear in `text`.
+pub fn check_text(text: &str, known: &Known) -> Vec<Diagnostic> {
+    let doc = match ImDocument::parse(text) {
+        Ok(doc) => doc,
+        Err(e) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                position: e.span().map(|s| position(text, s)),
+                message: e.message().trim().to_string(),
+            }];
+        }
+    };
+
+    let mut checker = Checker {
+        text,
+        known,
+        found: Vec::new(),
+    };
+    let root = doc.as_table();
+    checker.root(root, "");
+    checker.default_profile(root);
+    checker.top_words(root);
+
+    // Anything the walk above missed still stops the file from loading.
+    if let Err(e) = ConfigToml::parse(text, Profile::Base) {
+        if !checker.found.iter().any(|d| d.severity == Severity::Error) {
+            checker.report(Severity::Error, None, format!("{e:#}"));
+        }
+    }
+
+    let mut found = checker.found;
+    found.sort_by_key(|d| d.position);
+    found
+}
+
+struct Checker<'a> {
+    text: &'a str,
+    known: &'a Known,
+    found: Vec<Diagnostic>,
+}
+
+impl Checker<'_> {
+    fn report(&mut self, severity: Severity, span: Option<Range<usize>>, message: String) {
+        self.found.push(Diagnostic {
+            severity,
+            position: span.map(|s| position(self.text, s)),
+            message,
+        });
+    }
+
+    /// Checks the top level of the file, or of a profile when `prefix` is
+    /// `profiles.<name>.`.
+    fn root(&mut self, root: &dyn TableLike, prefix: &str) {
+        for (name, item) in root.iter() {
+            let key = root.key(name);
+            if !prefix.is_empty() && TOP_LEVEL_ONLY.contains(&name) {
+                let message = format!("`{prefix}{name}` only works at the top level");
+                self.report(Severity::Warning, key_span(key), message);
+            } else if name == "keybindings" {
+                self.keybindings(prefix, key, item);
+            } else if name == "profiles" {
+                self.profiles(key, item);
+            } else if schema::TABLES.contains(&name) {
+                self.table(prefix, name, key, item);
+            } else {
+                self.value(prefix, None, name, key, item);
+            }
+        }
+    }
+
+    fn profiles(&mut self, key: Option<&Key>, item: &Item) {
+        let Some(profiles) = item.as_table_like() else {
+            let message = format!("`profiles` must be a table, found {}", item.type_name());
+            self.report(Severity::Error, item.span().or(key_span(key)), message);
+            return;
+        };
+        for (name, profile) in profiles.iter() {
+            match profile.as_table_like() {
+                Some(table) => self.root(table, &format!("profiles.{name}.")),
+                None => {
+                    let message = format!(
+                        "`profiles.{name}` must be a table, found {}",
+                        profile.type_name()
+                    );
+                    self.report(Severity::Error, key_span(profiles.key(name)), message);
+                }
+            }
+        }
+    }
+
+    /// Warns when the `profile` key names a profile the file lacks.
+    fn default_profile(&mut self, root: &dyn TableLike) {
+        let Some(item) = root.get("profile") else {
+            return;
+        };
+        let Some(name) = item.as_str() else {
+            return;
+        };
+        let defined = root
+            .get("profiles")
+            .and_then(Item::as_table_like)
+            .is_some_and(|p| p.contains_key(name));
+        if !defined {
+            let message = format!("`profile`: unknown profile `{name}`, no profile is used");
+            self.report(Severity::Warning, item.span(), message);
+        }
+    }
+
+    /// Warns when `top_words` is set for a list that has no ranking.
+    fn top_words(&mut self, root: &dyn TableLike) {
+        let Some(language) = root.get("language").and_then(Item::as_table_like) else {
+            return;
+        };
+        let Some(item) = language.get("top_words") else {
+            return;
+        };
+        if item.as_integer().unwrap_or(0) <= 0 {
+            return;
+        }
+        let lang = language
+            .get("lang")
+            .and_then(Item::as_str)
+            .unwrap_or("english");
+        if self.known.languages.iter().any(|l| l == lang) && !(self.known.ranked)(lang) {
+            let message =
+                format!("`language.top_words`: `{lang}` is not a ranked list, every word is used");
+            self.report(Severity::Warning, item.span(), message);
+        }
+    }
+
+    fn table(&mut self, prefix: &str, name: &str, key: Option<&Key>, item: &Item) {
+        let Some(table) = item.as_table_like() else {
+            let message = format!(
+                "`{prefix}{name}` must be a table, found {}",
+                item.type_name()
+            );
+            self.report(Severity::Error, item.span().or(key_span(key)), message);
+            return;
+        };
+        for (field, value) in table.iter() {
+            self.value(prefix, Some(name), field, table.key(field), value);
+        }
+    }
+
+    fn value(
+        &mut self,
+        prefix: &str,
+        table: Option<&str>,
+        name: &str,
+        key: Option<&Key>,
+        item: &Item,
+    ) {
+        let path = match table {
+            Some(table) => format!("{prefix}{table}.{name}"),
+            None => format!("{prefix}{name}"),
+        };
+        let Some(spec) = schema::find(table, name) else {
+            self.report(
+                Severity::Warning,
+                key_span(key),
+                format!("unknown key `{path}`"),
+            );
+            return;
+        };
+        let problem = match item.as_value() {
+            Some(value) => spec.kind.check(value, self.known),
+            None => Some((
+                Severity::Error,
+                format!(
+                    "expected {}, found {}",
+                    spec.kind.expected(),
+                    item.type_name()
+                ),
+            )),
+        };
+        if let Some((severity, message)) = problem {
+            self.report(severity, item.span(), format!("`{path}`: {message}"));
+        }
+    }
+
+    fn keybindings(&mut self, prefix: &str, key: Option<&Key>, item: &Item) {
+        let Some(table) = item.as_table_like() else {
+            let message = format!(
+                "`{prefix}keybindings` must be a table, found {}",
+                item.type_name()
+            );
+            self.report(Severity::Error, item.span().or(key_span(key)), message);
+            return;
+        };
+
+        let mut overrides = BTreeMap::new();
+        for (action, value) in table.iter() {
+            if Action::from_name(action).is_none() {
+                let message = format!("unknown action `{action}` in [{prefix}keybindings]");
+                self.report(Severity::Warning, key_span(table.key(action)), message);
+                continue;
+            }
+            let chords: Option<Vec<&str>> = match value.as_value() {
+                Some(v) if v.is_str() => v.as_str().map(|s| vec![s]),
+                Some(v) => v
+                    .as_array()
+                    .and_then(|a| a.iter().map(|c| c.as_str()).collect()),
+                None => None,
+            };
+            let Some(chords) = chords else {
+                let message = format!(
+                    "`{prefix}keybindings.{action}`: expected a key or a list of keys, found {}",
+                    value.type_name()
+                );
+                self.report(Severity::Warning, value.span(), message);
+                continue;
+            };
+            match chords
+                .iter()
+                .try_for_each(|c| c.parse::<KeyChord>().map(drop))
+            {
+                Ok(()) => {
+                    let chords = chords.iter().map(|c| c.to_string()).collect();
+                    overrides.insert(action.to_string(), chords);
+                }
+                Err(e) => {
+                    let message = format!("`{prefix}keybindings.{action}`: {e}");
+                    self.report(Severity::Warning, value.span(), message);
+                }
+            }
+        }
+
+        if let Err(e) = Keymap::with_overrides(&overrides) {
+            self.report(
+                Severity::Warning,
+                item.span().or(key_span(key)),
+                e.to_string(),
+            );
+        }
+    }
+}
+
+fn key_span(key: Option<&Key>) -> Option<Range<usize>> {
+    key.and_then(Key::span)
+}
+
+/// The 1-based line and column where `span` starts.
+fn position(text: &str, span: Range<usize>) -> (usize, usize) {
+    let before = &text[..span.start.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod check_tests {
+    use super::*;
+
+    fn known() -> Known {
+        Known {
+            themes: vec!["nord".to_string()],
+            languages: vec!["english".to_string(), "english_200".to_string()],
+            ranked: |language| language == "english_200",
+        }
+    }
+
+    fn check(text: &str) -> Vec<String> {
+        check_text(text, &known())
+            .iter()
+            .map(|d| d.to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_valid_config() {
+        let text = r#"
+version = 1
+theme = "nord"
+cursor = "bar"
+
+[modes]
+default_mode = "uppercase, punctuation"
+uppercase_chance = "0.3"
+punctuation_chance = 0.1
+numbers_chance = 1
+
+[language]
+lang = "english_200"
+top_words = 100
+lazy = true
+
+[game]
+time = 60
+
+[keybindings]
+settings = ["ctrl+s", "s"]
+"#;
+        assert_eq!(check(text), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_syntax_error_has_a_position() {
+        let found = check("theme = \"nord\"\n[game\ntime = 30\n");
+        assert_eq!(found.len(), 1);
+        assert!(found[0].starts_with("2:6: error:"), "{found:?}");
+    }
+
+    #[test]
+    fn test_unknown_keys_and_wrong_types() {
+        let text = "colour = \"red\"\n[game]\ntime = \"60\"\nafk_timeout = -1\n";
+        assert_eq!(
+            check(text),
+            vec![
+                "1:1: warning: unknown key `colour`",
+                "3:8: error: `game.time`: expected a whole number from 1 to 3600, found string",
+                "4:15: error: `game.afk_timeout`: `-1` must not be negative",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_values_out_of_range_and_unknown_names() {
+        let text = r#"theme = "solarized"
+version = 3
+[modes]
+default_mode = "uppercase, shouting"
+uppercase_chance = "1.5"
+punctuation_chance = true
+[language]
+lang = "klingon"
+text_style = "poems"
+[game]
+time = 5000
+"#;
+        let found = check(text);
+        assert_eq!(found.len(), 8, "{found:?}");
+        assert!(found[0].starts_with("1:9: warning: `theme`: unknown theme `solarized`"));
+        assert!(found[1].starts_with("2:11: warning: `version`: version 3 is newer"));
+        assert!(found[2].contains("unknown mode `shouting`"));
+        assert!(found[3].contains("outside 0.0–1.0"));
+        assert!(found[4].starts_with("6:22: error: `modes.punctuation_chance`: expected a number"));
+        assert!(found[5].starts_with("8:8: error: `language.lang`: unknown language `klingon`"));
+        assert!(found[6].starts_with("9:14: warning:"));
+        assert!(found[7].ends_with("`5000` is too large, use at most 3600"));
+    }
+
+    #[test]
+    fn test_keybindings() {
+        let text = "[keybindings]\nfly = \"f\"\nquit = \"ctrl+nope\"\nstats = \"s\"\n";
+        let found = check(text);
+        assert_eq!(found.len(), 3, "{found:?}");
+        assert!(found[0].starts_with("1:1: warning: `s` is bound to both"));
+        assert!(found[1].starts_with("2:1: warning: unknown action `fly`"));
+        assert!(found[2].starts_with("3:8: warning: `keybindings.quit`"));
+    }
+
+    #[test]
+    fn test_top_words_needs_a_ranked_list() {
+        assert_eq!(
+            check("[language]\ntop_words = 1000\n"),
+            ["2:13: warning: `language.top_words`: `english` is not a ranked list, every word is used"]
+        );
+        assert!(check("[language]\nlang = \"english_200\"\ntop_words = 100\n").is_empty());
+        assert!(check("[language]\nlang = \"english\"\ntop_words = 0\n").is_empty());
+    }
+
+    #[test]
+    fn test_profiles() {
+        let text = r#"profile = "german"
+[profiles.warmup]
+theme = "nord"
+version = 1
+[profiles.warmup.game]
+time = "15"
+[profiles.code]
+fonts = 3
+[profiles.code.keybindings]
+fly = "f"
+"#;
+        assert_eq!(
+            check(text),
+            vec![
+                "1:11: warning: `profile`: unknown profile `german`, no profile is used",
+                "4:1: warning: `profiles.warmup.version` only works at the top level",
+                "6:8: error: `profiles.warmup.game.time`: expected a whole number from 1 to \
+                 3600, found string",
+                "8:1: warning: unknown key `profiles.code.fonts`",
+                "10:1: warning: unknown action `fly` in [profiles.code.keybindings]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_banner() {
+        let report = Report {
+            path: PathBuf::from("config.toml"),
+            diagnostics: check_text("[game]\ntime = \"60\"\nspeed = 3\n", &known()),
+            ignored: true,
+        };
+        assert_eq!((report.errors(), report.warnings()), (1, 1));
+        assert_eq!(
+            report.banner().unwrap(),
+            "config ignored: line 2: `game.time`: expected a whole number from 1 to 3600, \
+             found string (+1 more, see `typy config check`)"
+        );
+        assert_eq!(
+            report.lines()[1],
+            "config.toml:3:1: warning: unknown key `game.speed`"
+        );
+    }
+}
@@ -0,0 +1,39 @@
+use crate::config::toml_parser::ConfigToml;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AfkAction {
+    Pause,
+    Invalidate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AfkSettings {
+    /// Seconds without a keystroke before a running test counts as AFK; 0 disables it.
+    pub timeout: u64,
+    pub action: AfkAction,
+}
+
+impl AfkSettings {
+    pub fn new(config: &ConfigToml) -> Self {
+        let defaults = AfkSettings::default();
+        let action = config.get::<String>("game.afk_action");
+        let action = match action.as_deref().map(str::trim) {
+            Some("invalidate") => AfkAction::Invalidate,
+            Some("pause") => AfkAction::Pause,
+            _ => defaults.action,
+        };
+        AfkSettings {
+            timeout: config.get("game.afk_timeout").unwrap_or(defaults.timeout),
+            action,
+        }
+    }
+}
+
+impl Default for AfkSettings {
+    fn default() -> Self {
+        AfkSettings {
+            timeout: 10,
+            action: AfkAction::Pause,
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use dirs::home_dir;
+
+use crate::config::toml_parser::ConfigToml;
+use crate::word_provider::WordFilter;
+
+impl WordFilter {
+    /// Word filters from the `[filters]` table of the user's config.
+    pub(crate) fn from_config(config: &ConfigToml) -> Result<Self> {
+        let chars = |key| config.get::<String>(key).filter(|c| !c.is_empty());
+        let filter = WordFilter {
+            min_length: config.get("filters.min_length"),
+            max_length: config.get("filters.max_length"),
+            allowed_chars: chars("filters.allowed_chars"),
+            required_chars: chars("filters.required_chars"),
+            ..WordFilter::default()
+        };
+        match config.get::<String>("filters.blocklist") {
+            Some(path) => filter.with_blocklist_file(&expand_home(&path)),
+            None => Ok(filter),
+        }
+    }
+}
+
+pub(crate) fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,17 @@
+use crate::config::toml_parser::ConfigToml;
+use crate::keymap::Keymap;
+
+pub struct Keybindings {
+    pub keymap: Keymap,
+}
+
+impl Keybindings {
+    /// The bindings of `[keybindings]` over the defaults. Bindings that don't
+    /// parse or clash leave every key at its default; `typy config check`
+    /// and the notice line say why.
+    pub fn new(config: &ConfigToml) -> Self {
+        Keybindings {
+            keymap: Keymap::with_overrides(&config.keybindings()).unwrap_or_default(),
+        }
+    }
+}
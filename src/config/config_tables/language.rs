@@ -1,20 +1,32 @@
-use crate::config::toml_parser::get_config;
+use crate::config::toml_parser::ConfigToml;
+use crate::word_provider::{TextStyle, DEFAULT_MIRROR};
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct Language {
     pub lang: String,
+    pub mirror: String,
+    /// Restricts ranked word lists to their most frequent words; `0` is all.
+    pub top_words: usize,
+    /// Accepts letters typed without their accents.
+    pub lazy: bool,
+    pub text_style: TextStyle,
 }
 
 impl Language {
-    pub fn new() -> Self {
-        let theme_colors: Language = match get_config().lock().unwrap().get_language() {
-            Some(language) => {
-                let lang = language.lang.unwrap_or("english".to_string());
-
-                Language { lang }
-            }
-            None => Language::default(),
-        };
-        theme_colors
+    pub fn new(config: &ConfigToml) -> Self {
+        let defaults = Language::default();
+        Language {
+            lang: config.get("language.lang").unwrap_or(defaults.lang),
+            mirror: config.get("language.mirror").unwrap_or(defaults.mirror),
+            top_words: config
+                .get("language.top_words")
+                .unwrap_or(defaults.top_words),
+            lazy: config.get("language.lazy").unwrap_or(defaults.lazy),
+            text_style: config
+                .get::<String>("language.text_style")
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.text_style),
+        }
     }
 }
 
@@ -22,6 +34,10 @@ impl Default for Language {
     fn default() -> Self {
         Language {
             lang: "english".to_string(),
+            mirror: DEFAULT_MIRROR.to_string(),
+            top_words: 0,
+            lazy: false,
+            text_style: TextStyle::Words,
         }
     }
 }
@@ -1,2 +1,5 @@
+pub mod afk;
+pub mod filters;
 pub mod mode_settings;
 pub mod language;
+pub mod keybindings;
@@ -1,61 +1,32 @@
-use crate::config::toml_parser::get_config;
-use crate::mode::ModeType;
+use crate::config::model::Chance;
+use crate::config::toml_parser::ConfigToml;
+use crate::mode::{ModeSettings, ModeType};
 use std::str::FromStr;
 
-#[derive(Debug)]
-pub struct ModeSettings {
-    pub default_modes: Vec<ModeType>,
-    pub uppercase_chance: f32,
-    pub punctuation_chance: f32,
-}
-
 impl ModeSettings {
-    pub fn new() -> Self {
-        let theme_colors: ModeSettings = match get_config().lock().unwrap().get_modes() {
-            Some(settings) => {
-                let default_modes = settings
-                    .default_mode
-                    .map(|m| {
-                        let modes: Vec<ModeType> = m
-                            .split(',')
-                            .filter_map(|mode| ModeType::from_str(mode.trim()).ok())
-                            .collect();
-                        if modes.contains(&ModeType::Normal) {
-                            vec![ModeType::Normal]
-                        } else {
-                            modes
-                        }
-                    })
-                    .unwrap_or(vec![ModeType::Normal]);
-                let uppercase_chance = settings
-                    .uppercase_chance
-                    .and_then(|c| c.parse::<f32>().ok())
-                    .map(|c| c.clamp(0.0, 1.0))
-                    .unwrap_or(0.2);
-                let punctuation_chance = settings
-                    .punctuation_chance
-                    .and_then(|c| c.parse::<f32>().ok())
-                    .map(|c| c.clamp(0.0, 1.0))
-                    .unwrap_or(0.2);
-
-                ModeSettings {
-                    default_modes,
-                    uppercase_chance,
-                    punctuation_chance,
+    /// Mode settings from the `[modes]` table of the user's config.
+    pub(crate) fn from_config(config: &ConfigToml) -> Self {
+        let default_modes = config
+            .get::<String>("modes.default_mode")
+            .map(|m| {
+                let modes: Vec<ModeType> = m
+                    .split(',')
+                    .filter_map(|mode| ModeType::from_str(mode.trim()).ok())
+                    .collect();
+                if modes.contains(&ModeType::Normal) {
+                    vec![ModeType::Normal]
+                } else {
+                    modes
                 }
-            }
-            None => ModeSettings::default(),
-        };
-        theme_colors
-    }
-}
+            })
+            .unwrap_or(vec![ModeType::Normal]);
+        let chance = |key| config.get(key).unwrap_or(Chance::DEFAULT).get();
 
-impl Default for ModeSettings {
-    fn default() -> Self {
         ModeSettings {
-            default_modes: vec![ModeType::Normal],
-            uppercase_chance: 0.2,
-            punctuation_chance: 0.2,
+            default_modes,
+            uppercase_chance: chance("modes.uppercase_chance"),
+            punctuation_chance: chance("modes.punctuation_chance"),
+            numbers_chance: chance("modes.numbers_chance"),
         }
     }
 }
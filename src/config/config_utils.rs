@@ -1,87 +1,719 @@
-use anyhow::{Context, Result};
-use dirs::home_dir;
-use std::{fs, io::Write, path::PathBuf, process::Command};
-use toml_edit::{value, DocumentMut, Item, Table};
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io::Write, process::Command};
+use toml_edit::{value, Array, DocumentMut, Item, Table, TableLike};
+
+use super::model::{Chance, CONFIG_VERSION};
+use super::schema::{self, KeySpec, Kind, Known, KEYS};
+use super::settings::Settings;
+use super::toml_parser::{Profile, TOP_LEVEL_ONLY};
+use crate::keymap::{Action, KeyChord, Keymap};
+use crate::paths;
+use crate::settings::Preferences;
 
 pub fn create_config() -> Result<()> {
-    if let Some(home_path) = home_dir() {
-        let config_dir = home_path.join(".config/typy");
-        let config_file = config_dir.join("config.toml");
+    create_file(&config_path()?)
+}
 
+fn create_file(config_file: &Path) -> Result<()> {
+    if let Some(config_dir) = config_file.parent().filter(|d| !d.as_os_str().is_empty()) {
         if !config_dir.exists() {
-            fs::create_dir_all(&config_dir).context("Failed to create config directory")?;
+            fs::create_dir_all(config_dir).context("Failed to create config directory")?;
         }
+    }
 
-        if !config_file.exists() {
-            let mut file =
-                fs::File::create(&config_file).context("Failed to create config file")?;
+    if !config_file.exists() {
+        let mut file = fs::File::create(config_file).context("Failed to create config file")?;
 
-            file.write_all(b"# For more information about the configuration check:\n# https://github.com/Pazl27/typy-cli?tab=readme-ov-file#configuration")
-                .context("Failed to write to config file")?;
-        }
-    } else {
-        eprintln!("Failed to get home directory");
+        let header = format!(
+            "# For more information about the configuration check:\n\
+             # https://github.com/Pazl27/typy-cli?tab=readme-ov-file#configuration\n\n\
+             version = {CONFIG_VERSION}\n"
+        );
+        file.write_all(header.as_bytes())
+            .context("Failed to write to config file")?;
     }
     Ok(())
 }
 
-fn config_path() -> Result<PathBuf> {
-    let home = home_dir().context("Failed to get home directory")?;
-    Ok(home.join(".config/typy/config.toml"))
+/// The config file typy reads and writes to.
+pub fn config_path() -> Result<PathBuf> {
+    paths::config_file()
+        .context("Failed to find the config file; set XDG_CONFIG_HOME or TYPY_CONFIG")
 }
 
-pub fn save_settings(
-    theme: &str,
-    cursor: &str,
-    language: &str,
-    mode: &str,
-    time: u64,
-) -> Result<()> {
-    create_config()?;
+fn read_config(path: &Path) -> Result<DocumentMut> {
+    if !path.exists() {
+        return Ok(DocumentMut::new());
+    }
+    let text = fs::read_to_string(path).context("Failed to read config file")?;
+    text.parse::<DocumentMut>()
+        .context("Failed to parse config file")
+}
+
+/// Applies `edit` to the config file, keeping its comments and layout.
+fn edit_config<T>(edit: impl FnOnce(&mut DocumentMut) -> Result<T>) -> Result<T> {
+    edit_file(&config_path()?, edit)
+}
+
+fn edit_file<T>(path: &Path, edit: impl FnOnce(&mut DocumentMut) -> Result<T>) -> Result<T> {
+    create_file(path)?;
+    let mut doc = read_config(path)?;
+    let out = edit(&mut doc)?;
+    fs::write(path, doc.to_string()).context("Failed to write config file")?;
+    Ok(out)
+}
+
+/// Upgrades the config file to [`CONFIG_VERSION`], keeping its comments.
+/// Returns what changed, one line per key; nothing for a current file.
+pub fn migrate_config() -> Result<Vec<String>> {
     let path = config_path()?;
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut doc = read_config(&path)?;
+    let changes = migrate(&mut doc);
+    if !changes.is_empty() {
+        fs::write(&path, doc.to_string()).context("Failed to write config file")?;
+    }
+    Ok(changes)
+}
 
-    let text = fs::read_to_string(&path).context("Failed to read config file")?;
-    let mut doc = text
-        .parse::<DocumentMut>()
-        .context("Failed to parse config file")?;
+/// Brings `doc` up to [`CONFIG_VERSION`]: chances written as strings in
+/// version 0 become numbers. Newer files are left alone.
+fn migrate(doc: &mut DocumentMut) -> Vec<String> {
+    let version = doc.get("version").and_then(Item::as_integer).unwrap_or(0);
+    if version >= CONFIG_VERSION {
+        return Vec::new();
+    }
+    let mut changes = Vec::new();
+    if let Some(modes) = doc.get_mut("modes").and_then(Item::as_table_like_mut) {
+        for name in ["uppercase_chance", "punctuation_chance", "numbers_chance"] {
+            let Some(old) = modes.get(name).and_then(Item::as_str).map(str::to_string) else {
+                continue;
+            };
+            if let Ok(chance) = old.parse::<Chance>() {
+                set_item(modes, name, value(chance.value()));
+                changes.push(format!("modes.{name}: \"{old}\" -> {}", chance.value()));
+            }
+        }
+    }
+    set_item(doc.as_table_mut(), "version", value(CONFIG_VERSION));
+    changes.push(format!("version: {version} -> {CONFIG_VERSION}"));
+    changes
+}
 
-    doc.as_table_mut().insert("theme", value(theme));
-    doc.as_table_mut().insert("cursor", value(cursor));
-    set_kv(&mut doc, "language", "lang", value(language));
-    set_kv(&mut doc, "modes", "default_mode", value(mode));
-    set_kv(&mut doc, "game", "time", value(time as i64));
+/// Saves the settings page. Only values that differ from what is in effect
+/// are written, to the active profile's table if there is one. Returns the
+/// settings of the file as written.
+pub fn save_settings(preferences: &Preferences) -> Result<Settings> {
+    save_settings_at(&config_path()?, preferences)
+}
 
-    fs::write(&path, doc.to_string()).context("Failed to write config file")?;
-    Ok(())
+fn save_settings_at(path: &Path, preferences: &Preferences) -> Result<Settings> {
+    let text = edit_file(path, |doc| {
+        save_in(doc, preferences);
+        Ok(doc.to_string())
+    })?;
+    Settings::parse(&text, Profile::of(preferences.profile))
+}
+
+fn save_in(doc: &mut DocumentMut, preferences: &Preferences) {
+    let p = preferences;
+    for spec in KEYS.iter().filter(|spec| spec.kind != Kind::Profile) {
+        let Some(raw) = p.values.get(&spec.path()) else {
+            continue;
+        };
+        let Ok(item) = spec.kind.value(raw) else {
+            continue;
+        };
+        let setting = Setting::Key(spec);
+        let current = setting
+            .get_in_profile(doc, p.profile)
+            .or_else(|| setting.get(doc))
+            .map(display)
+            .or_else(|| setting.default_value());
+        let item = Item::Value(item);
+        if current.as_deref() != Some(display(&item).as_str()) {
+            setting.set(doc, p.profile, item);
+        }
+    }
+}
+
+/// Makes `profile` the one typy starts with; `None` uses no profile.
+/// Returns the settings of the file as written, with `profile` applied.
+pub fn save_profile(profile: Option<&str>) -> Result<Settings> {
+    let text = edit_config(|doc| {
+        match profile {
+            Some(name) => set_item(doc.as_table_mut(), "profile", value(name)),
+            None => drop(doc.as_table_mut().remove("profile")),
+        }
+        Ok(doc.to_string())
+    })?;
+    Settings::parse(&text, Profile::of(profile))
 }
 
-fn set_kv(doc: &mut DocumentMut, section: &str, key: &str, val: Item) {
-    let table = doc.as_table_mut();
-    if !table.contains_key(section) {
-        table.insert(section, Item::Table(Table::new()));
+/// The table at `path`, created (without a header of its own until it
+/// holds a value) where missing.
+fn table_at<'d>(doc: &'d mut DocumentMut, path: &[&str]) -> Option<&'d mut dyn TableLike> {
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for name in path {
+        if !table.contains_key(name) {
+            let mut new = Table::new();
+            new.set_implicit(true);
+            table.insert(name, Item::Table(new));
+        }
+        table = table.get_mut(name)?.as_table_like_mut()?;
     }
-    if let Some(section_table) = table[section].as_table_mut() {
-        section_table.insert(key, val);
+    Some(table)
+}
+
+/// Inserts `val`, keeping the comments around the value it replaces.
+fn set_item(table: &mut dyn TableLike, key: &str, mut val: Item) {
+    let Some(old) = table.get_mut(key) else {
+        table.insert(key, val);
+        return;
+    };
+    if let (Some(old), Some(new)) = (old.as_value(), val.as_value_mut()) {
+        *new.decor_mut() = old.decor().clone();
     }
+    *old = val;
 }
 
-pub fn open_config() -> Result<()> {
-    if let Some(home_path) = home_dir() {
-        let config_dir = home_path.join(".config/typy");
-        let config_file = config_dir.join("config.toml");
+/// A key that `typy config` can read and write, e.g. `game.time` or
+/// `keybindings.quit`.
+enum Setting {
+    Key(&'static KeySpec),
+    Binding(Action),
+}
+
+impl Setting {
+    fn parse(path: &str) -> Result<Self> {
+        if let Some(spec) = schema::find_path(path) {
+            return Ok(Setting::Key(spec));
+        }
+        match path.strip_prefix("keybindings.").map(Action::from_name) {
+            Some(Some(action)) => Ok(Setting::Binding(action)),
+            Some(None) => bail!("Unknown action in `{path}`"),
+            None => bail!("Unknown config key `{path}`"),
+        }
+    }
+
+    fn table(&self) -> Option<&'static str> {
+        match self {
+            Setting::Key(spec) => spec.table,
+            Setting::Binding(_) => Some("keybindings"),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Setting::Key(spec) => spec.name,
+            Setting::Binding(action) => action.name(),
+        }
+    }
+
+    fn default_value(&self) -> Option<String> {
+        match self {
+            Setting::Key(spec) => spec.default.map(str::to_string),
+            Setting::Binding(action) => Some(chords_label(Keymap::default().keys(*action))),
+        }
+    }
+
+    /// The value for `raw`, type checked against the schema. Key bindings
+    /// take space separated chords and must not clash with other bindings.
+    fn value(&self, raw: &str, doc: &DocumentMut, known: &Known) -> Result<Item> {
+        let action = match self {
+            Setting::Key(spec) => return Ok(Item::Value(spec.kind.parse(raw, known)?)),
+            Setting::Binding(action) => action,
+        };
+        let chords: Vec<String> = raw.split_whitespace().map(str::to_string).collect();
+        if chords.is_empty() {
+            bail!("expected one or more keys, e.g. \"ctrl+p :\"");
+        }
+        let mut overrides = bindings(doc);
+        overrides.insert(action.name().to_string(), chords.clone());
+        Keymap::with_overrides(&overrides)?;
+        Ok(match chords.as_slice() {
+            [chord] => value(chord),
+            _ => value(Array::from_iter(chords)),
+        })
+    }
+
+    /// The tables holding this key, inside `[profiles.<name>]` for a profile.
+    fn tables<'a>(&self, profile: Option<&'a str>) -> Vec<&'a str> {
+        let mut path = Vec::new();
+        if let Some(profile) = profile {
+            path.extend(["profiles", profile]);
+        }
+        path.extend(self.table());
+        path
+    }
+
+    fn get<'d>(&self, doc: &'d DocumentMut) -> Option<&'d Item> {
+        self.get_in_profile(doc, None)
+    }
+
+    fn get_in_profile<'d>(&self, doc: &'d DocumentMut, profile: Option<&str>) -> Option<&'d Item> {
+        let mut table: &dyn TableLike = doc.as_table();
+        for name in self.tables(profile) {
+            table = table.get(name)?.as_table_like()?;
+        }
+        table.get(self.name())
+    }
+
+    fn set(&self, doc: &mut DocumentMut, profile: Option<&str>, item: Item) {
+        if let Some(table) = table_at(doc, &self.tables(profile)) {
+            set_item(table, self.name(), item);
+        }
+    }
+
+    fn unset(&self, doc: &mut DocumentMut, profile: Option<&str>) -> bool {
+        let tables = self.tables(profile);
+        let Some(table) = table_at(doc, &tables) else {
+            return false;
+        };
+        let removed = table.remove(self.name()).is_some();
+        // drop tables left empty, innermost first, and hide the headers of
+        // tables that only hold other tables now
+        for depth in (1..=tables.len()).rev() {
+            let (parent, name) = (&tables[..depth - 1], tables[depth - 1]);
+            let Some(parent) = table_at(doc, parent) else {
+                break;
+            };
+            match parent.get_mut(name).and_then(Item::as_table_mut) {
+                Some(table) if table.is_empty() => {
+                    parent.remove(name);
+                }
+                Some(table) if table.iter().all(|(_, item)| item.is_table()) => {
+                    table.set_implicit(true)
+                }
+                _ => {}
+            }
+        }
+        removed
+    }
+}
+
+/// Splits `profiles.<name>.<key>` into the profile and the key.
+fn split_profile(key: &str) -> Result<(Option<&str>, &str)> {
+    let Some(rest) = key.strip_prefix("profiles.") else {
+        return Ok((None, key));
+    };
+    match rest.split_once('.') {
+        Some((profile, key)) if !profile.is_empty() => Ok((Some(profile), key)),
+        _ => bail!("Expected `profiles.<name>.<key>`, found `{key}`"),
+    }
+}
+
+fn chords_label(chords: &[KeyChord]) -> String {
+    chords
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The `[keybindings]` overrides in `doc` that parse.
+fn bindings(doc: &DocumentMut) -> BTreeMap<String, Vec<String>> {
+    let Some(table) = doc.get("keybindings").and_then(Item::as_table_like) else {
+        return BTreeMap::new();
+    };
+    table
+        .iter()
+        .filter_map(|(action, item)| Some((action.to_string(), item_strings(item)?)))
+        .collect()
+}
+
+fn item_strings(item: &Item) -> Option<Vec<String>> {
+    let value = item.as_value()?;
+    match value.as_str() {
+        Some(s) => Some(vec![s.to_string()]),
+        None => value
+            .as_array()?
+            .iter()
+            .map(|v| v.as_str().map(str::to_string))
+            .collect(),
+    }
+}
 
-        if !config_file.exists() {
-            eprintln!("Config file doesn't exist");
-            return Ok(());
+/// A value as typed on the command line: strings without quotes, lists of
+/// keys separated by spaces.
+fn display(item: &Item) -> String {
+    if let Some(strings) = item_strings(item) {
+        return strings.join(" ");
+    }
+    match item.as_value() {
+        Some(v) => {
+            let mut v = v.clone();
+            v.decor_mut().clear();
+            v.to_string()
         }
+        None => item.to_string().trim().to_string(),
+    }
+}
+
+/// A key as given to `typy config`: the setting, and the profile whose
+/// table it lives in, if any.
+fn parse_key(key: &str) -> Result<(Setting, Option<&str>)> {
+    let (profile, rest) = split_profile(key)?;
+    let setting = Setting::parse(rest)?;
+    if profile.is_some() && setting.table().is_none() && TOP_LEVEL_ONLY.contains(&rest) {
+        bail!("`{rest}` can't be set in a profile");
+    }
+    Ok((setting, profile))
+}
+
+fn get_in(doc: &DocumentMut, key: &str) -> Result<ConfigValue> {
+    let (setting, profile) = parse_key(key)?;
+    let item = match profile {
+        Some(_) => setting
+            .get_in_profile(doc, profile)
+            .or_else(|| setting.get(doc)),
+        None => setting.get(doc),
+    };
+    Ok(match item {
+        Some(item) => ConfigValue::Set(display(item)),
+        None => match setting.default_value() {
+            Some(default) => ConfigValue::Default(default),
+            None => ConfigValue::Unset,
+        },
+    })
+}
 
-        let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
-        Command::new(editor.clone())
-            .arg(config_file)
-            .status()
-            .with_context(|| format!("Failed to open config file with editor: {}", editor))?;
+fn set_in(doc: &mut DocumentMut, key: &str, raw: &str, known: &Known) -> Result<()> {
+    let (setting, profile) = parse_key(key)?;
+    let item = setting
+        .value(raw, doc, known)
+        .with_context(|| format!("Invalid value for `{key}`"))?;
+    setting.set(doc, profile, item);
+    Ok(())
+}
+
+fn unset_in(doc: &mut DocumentMut, key: &str) -> Result<bool> {
+    let (setting, profile) = parse_key(key)?;
+    if setting.get_in_profile(doc, profile).is_none() {
+        return Ok(false);
+    }
+    Ok(setting.unset(doc, profile))
+}
+
+/// The value of a key in the config file.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ConfigValue {
+    Set(String),
+    /// Not in the file, so typy uses this default.
+    Default(String),
+    /// Not in the file and without a default, e.g. a filter.
+    Unset,
+}
+
+/// Reads a dotted key such as `game.time` from the config file.
+pub fn get_value(key: &str) -> Result<ConfigValue> {
+    get_value_at(&config_path()?, key)
+}
+
+fn get_value_at(path: &Path, key: &str) -> Result<ConfigValue> {
+    get_in(&read_config(path)?, key)
+}
+
+/// Sets a dotted key, checking the value against the config schema.
+pub fn set_value(key: &str, raw: &str) -> Result<()> {
+    set_value_at(&config_path()?, key, raw)
+}
+
+fn set_value_at(path: &Path, key: &str, raw: &str) -> Result<()> {
+    let known = Known::current();
+    edit_file(path, |doc| set_in(doc, key, raw, &known))
+}
+
+/// Removes a key so typy falls back to its default; `false` if it was not set.
+pub fn unset_value(key: &str) -> Result<bool> {
+    parse_key(key)?;
+    if !config_path()?.exists() {
+        return Ok(false);
+    }
+    edit_config(|doc| unset_in(doc, key))
+}
+
+/// Replaces the config file with an empty one, keeping the old file next to
+/// it as `config.toml.bak`. Returns the backup, if there was a file.
+pub fn reset_config() -> Result<Option<PathBuf>> {
+    let path = config_path()?;
+    let backup = if path.exists() {
+        let mut backup = path.clone().into_os_string();
+        backup.push(".bak");
+        let backup = PathBuf::from(backup);
+        fs::rename(&path, &backup).context("Failed to back up config file")?;
+        Some(backup)
     } else {
-        eprintln!("Failed to get home directory");
+        None
+    };
+    create_config()?;
+    Ok(backup)
+}
+
+pub fn open_config() -> Result<()> {
+    let config_file = config_path()?;
+
+    if !config_file.exists() {
+        eprintln!("Config file doesn't exist");
+        return Ok(());
     }
+
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    Command::new(editor.clone())
+        .arg(config_file)
+        .status()
+        .with_context(|| format!("Failed to open config file with editor: {}", editor))?;
     Ok(())
 }
+
+#[cfg(test)]
+mod config_utils_tests {
+    use super::*;
+    use crate::config::toml_parser::ConfigToml;
+
+    const CONFIG: &str =
+        "# my config\ntheme = \"nord\"   # favourite\n\n[game]\ntime = 30 # seconds\n";
+
+    fn doc() -> DocumentMut {
+        CONFIG.parse().unwrap()
+    }
+
+    fn known() -> Known {
+        Known {
+            themes: vec!["nord".to_string(), "dracula".to_string()],
+            languages: vec!["english".to_string()],
+            ranked: |_| false,
+        }
+    }
+
+    #[test]
+    fn test_set_keeps_comments() {
+        let mut doc = doc();
+        set_in(&mut doc, "game.time", "60", &known()).unwrap();
+        set_in(&mut doc, "theme", "dracula", &known()).unwrap();
+        set_in(&mut doc, "language.lazy", "true", &known()).unwrap();
+        assert_eq!(
+            doc.to_string(),
+            "# my config\ntheme = \"dracula\"   # favourite\n\n[game]\ntime = 60 # seconds\n\
+             \n[language]\nlazy = true\n"
+        );
+    }
+
+    #[test]
+    fn test_set_checks_types() {
+        let mut doc = doc();
+        for (key, raw) in [
+            ("game.time", "soon"),
+            ("game.time", "-5"),
+            ("language.lazy", "yes"),
+            ("theme", "solarized"),
+            ("modes.uppercase_chance", "2"),
+            ("cursor", "triangle"),
+            ("game.speed", "1"),
+        ] {
+            assert!(
+                set_in(&mut doc, key, raw, &known()).is_err(),
+                "{key} = {raw}"
+            );
+        }
+        assert_eq!(doc.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn test_keybindings() {
+        let mut doc = doc();
+        set_in(&mut doc, "keybindings.palette", "ctrl+k :", &known()).unwrap();
+        assert_eq!(
+            get_in(&doc, "keybindings.palette").unwrap(),
+            ConfigValue::Set("ctrl+k :".to_string())
+        );
+        assert_eq!(
+            get_in(&doc, "keybindings.quit").unwrap(),
+            ConfigValue::Default("q esc".to_string())
+        );
+        let err = set_in(&mut doc, "keybindings.stats", "s", &known()).unwrap_err();
+        assert!(format!("{err:#}").contains("`s` is bound to both"));
+        assert!(set_in(&mut doc, "keybindings.fly", "f", &known()).is_err());
+    }
+
+    #[test]
+    fn test_migrate_version_0() {
+        let mut doc: DocumentMut = "theme = \"nord\"\n\n[modes]\n\
+            uppercase_chance = \"0.3\" # rarely\nnumbers_chance = \"lots\"\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            migrate(&mut doc),
+            ["modes.uppercase_chance: \"0.3\" -> 0.3", "version: 0 -> 1"]
+        );
+        assert_eq!(
+            doc.to_string(),
+            "theme = \"nord\"\nversion = 1\n\n[modes]\n\
+             uppercase_chance = 0.3 # rarely\nnumbers_chance = \"lots\"\n"
+        );
+
+        let mut newer: DocumentMut = "version = 2\n[modes]\nuppercase_chance = \"0.3\"\n"
+            .parse()
+            .unwrap();
+        assert!(migrate(&mut newer).is_empty());
+        assert!(newer.to_string().contains("uppercase_chance = \"0.3\""));
+    }
+
+    fn preferences<'a>(
+        theme: &str,
+        time: u64,
+        profile: Option<&'a str>,
+        mode: &str,
+    ) -> Preferences<'a> {
+        let values = [
+            ("theme", theme),
+            ("cursor", "block"),
+            ("language.lang", "english"),
+            ("language.top_words", "0"),
+            ("language.lazy", "false"),
+            ("language.text_style", "words"),
+            ("modes.default_mode", mode),
+            ("game.time", &time.to_string()),
+        ];
+        Preferences {
+            values: values
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect(),
+            ranked: None,
+            profile,
+            profiles: &[],
+        }
+    }
+
+    #[test]
+    fn test_save_writes_only_changes() {
+        let normal = "normal";
+        let mut doc = self::doc();
+        save_in(&mut doc, &preferences("nord", 60, None, normal));
+        assert_eq!(
+            doc.to_string(),
+            "# my config\ntheme = \"nord\"   # favourite\n\n[game]\ntime = 60 # seconds\n"
+        );
+
+        let mut doc = self::doc();
+        save_in(&mut doc, &preferences("dracula", 30, Some("code"), normal));
+        assert!(doc.to_string().contains("theme = \"nord\""));
+        assert!(doc
+            .to_string()
+            .ends_with("[profiles.code]\ntheme = \"dracula\"\n"));
+        // the profile's own value is what is in effect from now on
+        save_in(&mut doc, &preferences("dracula", 15, Some("code"), normal));
+        assert_eq!(
+            get_in(&doc, "profiles.code.game.time").unwrap(),
+            ConfigValue::Set("15".to_string())
+        );
+        assert_eq!(
+            get_in(&doc, "game.time").unwrap(),
+            ConfigValue::Set("30".to_string())
+        );
+    }
+
+    #[test]
+    fn test_profile_keys() {
+        let mut doc = doc();
+        set_in(&mut doc, "profiles.warmup.game.time", "15", &known()).unwrap();
+        set_in(&mut doc, "profiles.warmup.theme", "dracula", &known()).unwrap();
+        assert_eq!(
+            get_in(&doc, "profiles.warmup.game.time").unwrap(),
+            ConfigValue::Set("15".to_string())
+        );
+        // keys a profile doesn't set come from the base settings
+        assert_eq!(
+            get_in(&doc, "profiles.code.theme").unwrap(),
+            ConfigValue::Set("nord".to_string())
+        );
+        assert!(set_in(&mut doc, "profiles.warmup.version", "1", &known()).is_err());
+        assert!(set_in(&mut doc, "profiles..theme", "nord", &known()).is_err());
+
+        assert!(unset_in(&mut doc, "profiles.warmup.theme").unwrap());
+        assert!(!doc.to_string().contains("[profiles.warmup]\n"));
+        assert!(unset_in(&mut doc, "profiles.warmup.game.time").unwrap());
+        assert!(!unset_in(&mut doc, "profiles.code.theme").unwrap());
+        assert_eq!(doc.to_string(), CONFIG);
+    }
+
+    #[test]
+    fn test_get_and_unset() {
+        let mut doc = doc();
+        assert_eq!(
+            get_in(&doc, "game.time").unwrap(),
+            ConfigValue::Set("30".to_string())
+        );
+        assert_eq!(
+            get_in(&doc, "game.afk_action").unwrap(),
+            ConfigValue::Default("pause".to_string())
+        );
+        assert_eq!(
+            get_in(&doc, "filters.min_length").unwrap(),
+            ConfigValue::Unset
+        );
+
+        assert!(unset_in(&mut doc, "game.time").unwrap());
+        assert!(!unset_in(&mut doc, "game.time").unwrap());
+        assert!(unset_in(&mut doc, "theme").unwrap());
+        assert_eq!(doc.to_string(), "");
+    }
+
+    #[test]
+    fn test_get_and_set_file() {
+        // a `config.toml` in the working directory, which typy then reads
+        // and writes instead of the default file
+        let dir = std::env::temp_dir().join(format!("typy-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        let text = "[game]\ntime = 60\n[modes]\nuppercase_chance = \"0.5\"\n";
+        fs::write(&path, text).unwrap();
+
+        assert_eq!(
+            get_value_at(&path, "game.time").unwrap(),
+            ConfigValue::Set("60".to_string())
+        );
+        set_value_at(&path, "game.time", "90").unwrap();
+        assert_eq!(
+            get_value_at(&path, "game.time").unwrap(),
+            ConfigValue::Set("90".to_string())
+        );
+        // setting a key leaves the rest of an old file as it was
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(!text.contains("version"));
+        assert!(text.contains("uppercase_chance = \"0.5\""));
+        let config = ConfigToml::read(&path, Profile::Default).unwrap();
+        assert_eq!(Settings::from_toml(config).unwrap().time.get(), 90);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_save_keeps_the_rest_of_the_file() {
+        let dir = std::env::temp_dir().join(format!("typy-save-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        fs::write(
+            &path,
+            "[game]\nafk_timeout = 0\n[keybindings]\nquick = \"ctrl+r\"\n\
+             [profiles.code.game]\ntime = 15\n",
+        )
+        .unwrap();
+
+        let normal = "normal";
+        let settings = save_settings_at(&path, &preferences("nord", 60, None, normal)).unwrap();
+        assert_eq!(settings.theme, "nord");
+        assert_eq!(settings.time.get(), 60);
+        assert_eq!(settings.afk.timeout, 0);
+        assert_eq!(settings.profiles, vec!["code"]);
+        assert_ne!(settings.keymap, Keymap::default());
+
+        let code = save_settings_at(&path, &preferences("nord", 30, Some("code"), normal));
+        assert_eq!(code.unwrap().time.get(), 30);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
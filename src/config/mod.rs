@@ -1,6 +1,12 @@
+pub mod check;
 mod config_tables;
 mod config_utils;
+pub mod model;
+pub mod schema;
+mod settings;
 pub mod toml_parser;
+pub mod watch;
 
 pub use config_tables::*;
 pub use config_utils::*;
+pub use settings::Settings;
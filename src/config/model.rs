@@ -0,0 +1,165 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The config format this build writes. Files without a `version` key are
+/// version 0, where chances were strings such as `"0.3"`.
+pub const CONFIG_VERSION: i64 = 1;
+
+/// Test durations offered by the settings page, quick menu and palette.
+pub const TIME_PRESETS: &[u64] = &[15, 30, 60, 120];
+
+/// A probability between 0 and 1, e.g. how often a word is capitalised.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Serialize)]
+pub struct Chance(f64);
+
+impl Chance {
+    pub const DEFAULT: Chance = Chance(0.2);
+
+    /// `chance` clamped to 0.0–1.0.
+    pub fn new(chance: f64) -> Self {
+        Chance(chance.clamp(0.0, 1.0))
+    }
+
+    pub fn get(self) -> f32 {
+        self.0 as f32
+    }
+
+    pub fn value(self) -> f64 {
+        self.0
+    }
+}
+
+impl FromStr for Chance {
+    type Err = anyhow::Error;
+
+    /// Parses a number, clamping it to 0.0–1.0.
+    fn from_str(input: &str) -> Result<Self> {
+        match input.trim().parse::<f64>() {
+            Ok(chance) if chance.is_finite() => Ok(Chance::new(chance)),
+            _ => bail!("`{input}` is not a number"),
+        }
+    }
+}
+
+/// How a chance may be written: a number, or a string in version 0 files.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawChance {
+    Integer(i64),
+    Float(f64),
+    Text(String),
+}
+
+impl<'de> Deserialize<'de> for Chance {
+    /// Reads a number or a numeric string, clamping it to 0.0–1.0. Other
+    /// strings fail, so typy falls back to the default like older versions.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        match RawChance::deserialize(d)? {
+            RawChance::Integer(chance) => Ok(Chance::new(chance as f64)),
+            RawChance::Float(chance) => Ok(Chance::new(chance)),
+            RawChance::Text(text) => text.parse().map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+/// How long a test runs, from [`Seconds::MIN`] to [`Seconds::MAX`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Seconds(u64);
+
+impl Seconds {
+    pub const MIN: u64 = 1;
+    pub const MAX: u64 = 3600;
+    pub const DEFAULT: Seconds = Seconds(30);
+
+    /// `seconds` clamped to the allowed range.
+    pub fn new(seconds: u64) -> Self {
+        Seconds(seconds.clamp(Self::MIN, Self::MAX))
+    }
+
+    pub fn get(self) -> u64 {
+        self.0
+    }
+}
+
+impl FromStr for Seconds {
+    type Err = anyhow::Error;
+
+    /// Parses `90`, `90s` or `2m`, rejecting durations out of range.
+    fn from_str(input: &str) -> Result<Self> {
+        let input = input.trim();
+        let (number, unit) = match input.strip_suffix('m') {
+            Some(minutes) => (minutes, 60),
+            None => (input.strip_suffix('s').unwrap_or(input), 1),
+        };
+        let seconds = number
+            .parse::<u64>()
+            .ok()
+            .and_then(|n| n.checked_mul(unit))
+            .ok_or_else(|| anyhow!("`{input}` is not a duration such as 90 or 2m"))?;
+        if !(Self::MIN..=Self::MAX).contains(&seconds) {
+            bail!("use {} to {} seconds, not {seconds}", Self::MIN, Self::MAX);
+        }
+        Ok(Seconds(seconds))
+    }
+}
+
+impl fmt::Display for Seconds {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Seconds {
+    /// Reads whole seconds, clamping them to the allowed range.
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        u64::deserialize(d).map(Seconds::new)
+    }
+}
+
+#[cfg(test)]
+mod model_tests {
+    use super::*;
+
+    #[derive(Deserialize)]
+    struct Modes {
+        chance: Chance,
+    }
+
+    fn chance(toml: &str) -> Option<f32> {
+        toml::from_str::<Modes>(toml).ok().map(|m| m.chance.get())
+    }
+
+    #[test]
+    fn test_chances() {
+        assert_eq!(chance("chance = 0.3"), Some(0.3));
+        assert_eq!(chance("chance = 1"), Some(1.0));
+        assert_eq!(chance("chance = \"0.5\""), Some(0.5));
+        assert_eq!(chance("chance = \"1.5\""), Some(1.0));
+        assert_eq!(chance("chance = -2.0"), Some(0.0));
+        assert_eq!(chance("chance = \"often\""), None);
+        assert_eq!(chance("chance = true"), None);
+        assert!("nan".parse::<Chance>().is_err());
+    }
+
+    #[test]
+    fn test_seconds() {
+        assert_eq!("90".parse::<Seconds>().unwrap().get(), 90);
+        assert_eq!(" 45s ".parse::<Seconds>().unwrap().get(), 45);
+        assert_eq!("2m".parse::<Seconds>().unwrap().get(), 120);
+        for bad in ["0", "3601", "61m", "soon", "-5", ""] {
+            assert!(bad.parse::<Seconds>().is_err(), "{bad}");
+        }
+        assert_eq!(Seconds::new(0).get(), Seconds::MIN);
+
+        #[derive(Deserialize)]
+        struct Game {
+            time: Seconds,
+        }
+        let time = |toml: &str| toml::from_str::<Game>(toml).ok().map(|g| g.time.get());
+        assert_eq!(time("time = 99999"), Some(Seconds::MAX));
+        assert_eq!(time("time = -1"), None);
+    }
+}
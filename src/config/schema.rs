@@ -0,0 +1,352 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+
+use toml_edit::Value;
+
+use super::filters::expand_home;
+use super::model::{Seconds, CONFIG_VERSION};
+use crate::mode::ModeType;
+use crate::settings::{ACCENT_OPTIONS, CURSOR_OPTIONS};
+use crate::theme::{available_themes, DEFAULT_THEME};
+use crate::word_provider::{available_languages, ranked_size, TextStyle, DEFAULT_MIRROR};
+
+/// How bad a config problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The value is ignored or adjusted, typy still starts.
+    Warning,
+    /// The file is ignored, or typy refuses to start.
+    Error,
+}
+
+impl Severity {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+/// Names that depend on what is installed, looked up once per check.
+pub struct Known {
+    pub themes: Vec<String>,
+    pub languages: Vec<String>,
+    /// Whether the list of a language is ranked, so `top_words` applies.
+    /// Only asked about the configured language, which saves loading
+    /// every list.
+    pub ranked: fn(&str) -> bool,
+}
+
+impl Known {
+    pub fn current() -> Self {
+        Known {
+            themes: available_themes(),
+            languages: available_languages(),
+            ranked: |language| ranked_size(language).is_some(),
+        }
+    }
+}
+
+/// What a config value must look like.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Text,
+    /// The name of a `[profiles.<name>]` table.
+    Profile,
+    OneOf(&'static [&'static str]),
+    Bool,
+    /// A whole number from `min` to `max`; negative numbers never load.
+    Integer {
+        min: i64,
+        max: i64,
+    },
+    /// The length of a test in seconds, from [`Seconds::MIN`] to
+    /// [`Seconds::MAX`].
+    Time,
+    /// How many of a ranked list's words are used; `0` is all.
+    TopWords,
+    /// A probability such as `0.3`; version 0 files wrote it as a string.
+    Chance,
+    /// The format version, at most [`CONFIG_VERSION`].
+    Version,
+    /// Comma separated mode names, e.g. `"uppercase, punctuation"`.
+    Modes,
+    Theme,
+    Language,
+    /// A path to an existing file; `~/` is expanded.
+    File,
+}
+
+impl Kind {
+    /// The kind a value is checked as; `Profile`, `Time` and `TopWords` only
+    /// differ in the choices the settings page offers.
+    fn base(&self) -> Kind {
+        match self {
+            Kind::Profile => Kind::Text,
+            Kind::Time => Kind::Integer {
+                min: Seconds::MIN as i64,
+                max: Seconds::MAX as i64,
+            },
+            Kind::TopWords => at_least(0),
+            kind => *kind,
+        }
+    }
+
+    pub fn expected(&self) -> String {
+        match self.base() {
+            Kind::Text | Kind::Theme | Kind::Language | Kind::File => "a string".to_string(),
+            Kind::OneOf(options) => format!("one of {}", quoted(options)),
+            Kind::Bool => "true or false".to_string(),
+            Kind::Integer { min, max: i64::MAX } => format!("a whole number of at least {min}"),
+            Kind::Integer { min, max } => format!("a whole number from {min} to {max}"),
+            Kind::Version => "a whole number".to_string(),
+            Kind::Chance => "a number such as 0.3".to_string(),
+            Kind::Modes => "a string such as \"uppercase, punctuation\"".to_string(),
+            Kind::Profile | Kind::Time | Kind::TopWords => unreachable!("not a base kind"),
+        }
+    }
+
+    /// Whether typy can read `value` from the file at all. A value it reads
+    /// may still be ignored, like an unknown theme, but one it can't read
+    /// makes the whole file fail to load.
+    pub fn loads(&self, value: &toml::Value) -> bool {
+        match self.base() {
+            Kind::Bool => value.is_bool(),
+            Kind::Integer { .. } => value.as_integer().is_some_and(|n| n >= 0),
+            Kind::Version => value.is_integer(),
+            Kind::Chance => value.is_integer() || value.is_float() || value.is_str(),
+            _ => value.is_str(),
+        }
+    }
+
+    /// Turns a value typed on the command line into a TOML value of this
+    /// kind, rejecting anything [`Kind::check`] would complain about.
+    pub fn parse(&self, raw: &str, known: &Known) -> Result<Value> {
+        let value = self.value(raw)?;
+        match self.check(&value, known) {
+            Some((_, message)) => bail!(message),
+            None => Ok(value),
+        }
+    }
+
+    /// `raw` as a TOML value of this kind, without checking the value.
+    pub fn value(&self, raw: &str) -> Result<Value> {
+        Ok(match self.base() {
+            Kind::Bool => Value::from(
+                raw.parse::<bool>()
+                    .map_err(|_| anyhow!("expected true or false, found `{raw}`"))?,
+            ),
+            Kind::Integer { .. } | Kind::Version => Value::from(
+                raw.parse::<i64>()
+                    .map_err(|_| anyhow!("expected {}, found `{raw}`", self.expected()))?,
+            ),
+            Kind::Chance => Value::from(
+                raw.trim()
+                    .parse::<f64>()
+                    .map_err(|_| anyhow!("expected {}, found `{raw}`", self.expected()))?,
+            ),
+            _ => Value::from(raw),
+        })
+    }
+
+    /// Checks one value; `None` means it is fine.
+    pub fn check(&self, value: &Value, known: &Known) -> Option<(Severity, String)> {
+        if self.base() != *self {
+            return self.base().check(value, known);
+        }
+        let mismatch = || {
+            Some((
+                Severity::Error,
+                format!("expected {}, found {}", self.expected(), value.type_name()),
+            ))
+        };
+        let warn = |message: String| Some((Severity::Warning, message));
+
+        if let Kind::Bool = self {
+            return value.as_bool().map_or_else(mismatch, |_| None);
+        }
+        if let Kind::Integer { min, max } = self {
+            return match value.as_integer() {
+                None => mismatch(),
+                Some(n) if n < 0 => Some((Severity::Error, format!("`{n}` must not be negative"))),
+                Some(n) if n < *min => warn(format!("`{n}` is too small, use at least {min}")),
+                Some(n) if n > *max => warn(format!("`{n}` is too large, use at most {max}")),
+                Some(_) => None,
+            };
+        }
+        if let Kind::Version = self {
+            return match value.as_integer() {
+                None => mismatch(),
+                Some(n) if n > CONFIG_VERSION => warn(format!(
+                    "version {n} is newer than this typy understands ({CONFIG_VERSION})"
+                )),
+                Some(_) => None,
+            };
+        }
+        if let Kind::Chance = self {
+            let chance = match (value.as_float(), value.as_integer(), value.as_str()) {
+                (Some(f), ..) => f,
+                (_, Some(n), _) => n as f64,
+                (.., Some(text)) => match text.trim().parse::<f64>() {
+                    Ok(c) if c.is_finite() => c,
+                    _ => return warn(format!("`{text}` is not a number")),
+                },
+                _ => return mismatch(),
+            };
+            return (!(0.0..=1.0).contains(&chance))
+                .then(|| warn(format!("`{chance}` is outside 0.0–1.0 and is clamped")))
+                .flatten();
+        }
+        let Some(text) = value.as_str() else {
+            return mismatch();
+        };
+        match self {
+            Kind::OneOf(options) if !options.contains(&text.trim()) => {
+                warn(format!("`{text}` is not one of {}", quoted(options)))
+            }
+            Kind::Modes => {
+                let unknown: Vec<&str> = text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|m| !m.is_empty() && ModeType::from_str(m).is_err())
+                    .collect();
+                (!unknown.is_empty()).then(|| {
+                    (
+                        Severity::Warning,
+                        format!("unknown mode {} is ignored", quoted(&unknown)),
+                    )
+                })
+            }
+            Kind::Theme if !known.themes.iter().any(|t| t == text) => {
+                warn(format!("unknown theme `{text}`"))
+            }
+            Kind::Language if !known.languages.iter().any(|l| l == text) => Some((
+                Severity::Error,
+                format!("unknown language `{text}`; run `typy lang install {text}` to download it"),
+            )),
+            Kind::File if !expand_home(text).is_file() => {
+                Some((Severity::Error, format!("file `{text}` does not exist")))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn quoted(items: &[&str]) -> String {
+    items
+        .iter()
+        .map(|i| format!("`{i}`"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// How a key is offered on the settings page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Row {
+    pub label: &'static str,
+    /// What a true-or-false key shows for `false` and `true`.
+    pub names: Option<[&'static str; 2]>,
+}
+
+/// A key the config file understands; `table` is `None` at the top level.
+pub struct KeySpec {
+    pub table: Option<&'static str>,
+    pub name: &'static str,
+    pub kind: Kind,
+    /// What typy uses when the key is not set, if anything.
+    pub default: Option<&'static str>,
+    /// Its row on the settings page; the rows follow the order of [`KEYS`].
+    pub row: Option<Row>,
+}
+
+impl KeySpec {
+    /// The dotted key, e.g. `game.time`.
+    pub fn path(&self) -> String {
+        match self.table {
+            Some(table) => format!("{table}.{}", self.name),
+            None => self.name.to_string(),
+        }
+    }
+
+    const fn row(self, label: &'static str) -> Self {
+        let row = Some(Row { label, names: None });
+        KeySpec { row, ..self }
+    }
+
+    const fn switch(self, label: &'static str, names: [&'static str; 2]) -> Self {
+        let row = Some(Row {
+            label,
+            names: Some(names),
+        });
+        KeySpec { row, ..self }
+    }
+}
+
+const fn key(
+    table: Option<&'static str>,
+    name: &'static str,
+    kind: Kind,
+    default: Option<&'static str>,
+) -> KeySpec {
+    KeySpec {
+        table,
+        name,
+        kind,
+        default,
+        row: None,
+    }
+}
+
+const fn at_least(min: i64) -> Kind {
+    Kind::Integer { min, max: i64::MAX }
+}
+
+const MODES: Option<&str> = Some("modes");
+const LANGUAGE: Option<&str> = Some("language");
+const GAME: Option<&str> = Some("game");
+const FILTERS: Option<&str> = Some("filters");
+
+/// Every key typy reads, the one description of the config file: loading
+/// it, `typy config`, `typy config check` and the settings page all work
+/// from this table.
+#[rustfmt::skip]
+pub const KEYS: &[KeySpec] = &[
+    key(None, "version", Kind::Version, None),
+    key(None, "profile", Kind::Profile, None).row("profile"),
+    key(None, "theme", Kind::Theme, Some(DEFAULT_THEME)).row("theme"),
+    key(None, "cursor", Kind::OneOf(CURSOR_OPTIONS), Some("block")).row("cursor"),
+    key(LANGUAGE, "lang", Kind::Language, Some("english")).row("language"),
+    key(LANGUAGE, "text_style", Kind::OneOf(TextStyle::NAMES), Some("words")).row("text"),
+    key(LANGUAGE, "top_words", Kind::TopWords, Some("0")).row("words"),
+    key(LANGUAGE, "lazy", Kind::Bool, Some("false")).switch("accents", ACCENT_OPTIONS),
+    key(LANGUAGE, "mirror", Kind::Text, Some(DEFAULT_MIRROR)),
+    key(MODES, "default_mode", Kind::Modes, Some("normal")).row("mode"),
+    key(MODES, "uppercase_chance", Kind::Chance, Some("0.2")),
+    key(MODES, "punctuation_chance", Kind::Chance, Some("0.2")),
+    key(MODES, "numbers_chance", Kind::Chance, Some("0.2")),
+    key(GAME, "time", Kind::Time, Some("30")).row("time"),
+    key(GAME, "afk_timeout", at_least(0), Some("10")),
+    key(GAME, "afk_action", Kind::OneOf(&["pause", "invalidate"]), Some("pause")),
+    key(FILTERS, "min_length", at_least(1), None),
+    key(FILTERS, "max_length", at_least(1), None),
+    key(FILTERS, "allowed_chars", Kind::Text, None),
+    key(FILTERS, "required_chars", Kind::Text, None),
+    key(FILTERS, "blocklist", Kind::File, None),
+];
+
+/// Tables whose keys are listed in [`KEYS`]; `[keybindings]` is free-form.
+pub const TABLES: &[&str] = &["modes", "language", "game", "filters"];
+
+pub fn find(table: Option<&str>, name: &str) -> Option<&'static KeySpec> {
+    KEYS.iter().find(|k| k.table == table && k.name == name)
+}
+
+/// Looks up a dotted key such as `game.time` or `theme`.
+pub fn find_path(path: &str) -> Option<&'static KeySpec> {
+    match path.split_once('.') {
+        Some((table, name)) => find(Some(table), name),
+        None => find(None, path),
+    }
+}
@@ -0,0 +1,144 @@
+use anyhow::{Context, Result};
+
+use super::afk::AfkSettings;
+use super::keybindings::Keybindings;
+use super::language::Language;
+use super::model::Seconds;
+use super::toml_parser::{ConfigToml, Profile};
+use crate::keymap::Keymap;
+use crate::mode::ModeSettings;
+use crate::theme::DEFAULT_THEME;
+use crate::word_provider::WordFilter;
+
+/// Everything typy takes from the config file, with the profile applied and
+/// defaults filled in. The app owns one value and replaces it as a whole
+/// when the file is saved or edited, so no part of it is ever stale.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub theme: String,
+    pub cursor: String,
+    pub time: Seconds,
+    pub modes: ModeSettings,
+    pub language: Language,
+    pub afk: AfkSettings,
+    pub filter: WordFilter,
+    pub keymap: Keymap,
+    /// The profile applied to the values above, if any.
+    pub profile: Option<String>,
+    /// The profiles defined in the config file.
+    pub profiles: Vec<String>,
+}
+
+impl Settings {
+    /// Reads the config file with `profile` applied; the defaults if there
+    /// is no file.
+    pub fn load(profile: Profile) -> Result<Self> {
+        Settings::from_toml(ConfigToml::load(profile)?)
+    }
+
+    /// Reads config `text` with `profile` applied.
+    pub fn parse(text: &str, profile: Profile) -> Result<Self> {
+        Settings::from_toml(ConfigToml::parse(text, profile)?)
+    }
+
+    /// Resolves the tables of a parsed config file. Fails on a blocklist
+    /// that can't be read; bad key bindings fall back to the defaults.
+    pub fn from_toml(config: ConfigToml) -> Result<Self> {
+        Ok(Settings {
+            theme: config
+                .get("theme")
+                .unwrap_or_else(|| DEFAULT_THEME.to_string()),
+            cursor: config.get("cursor").unwrap_or_else(|| "block".to_string()),
+            time: config.get("game.time").unwrap_or(Seconds::DEFAULT),
+            modes: ModeSettings::from_config(&config),
+            language: Language::new(&config),
+            afk: AfkSettings::new(&config),
+            filter: WordFilter::from_config(&config).context("Invalid word filters")?,
+            keymap: Keybindings::new(&config).keymap,
+            profile: config.active_profile(),
+            profiles: config.profile_names(),
+        })
+    }
+
+    /// The names of the default modes, e.g. `["uppercase", "punctuation"]`.
+    pub fn mode_tokens(&self) -> Vec<String> {
+        self.modes
+            .default_modes
+            .iter()
+            .map(|m| m.token().to_string())
+            .collect()
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: DEFAULT_THEME.to_string(),
+            cursor: "block".to_string(),
+            time: Seconds::DEFAULT,
+            modes: ModeSettings::default(),
+            language: Language::default(),
+            afk: AfkSettings::default(),
+            filter: WordFilter::default(),
+            keymap: Keymap::default(),
+            profile: None,
+            profiles: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+cursor = "bar"
+
+[modes]
+default_mode = "uppercase, punctuation"
+numbers_chance = 0.5
+
+[game]
+time = 45
+
+[keybindings]
+quick = "ctrl+r"
+
+[profiles.code.game]
+time = 90
+"#;
+
+    #[test]
+    fn test_defaults() {
+        assert_eq!(
+            Settings::parse("", Profile::Default).unwrap(),
+            Settings::default()
+        );
+    }
+
+    #[test]
+    fn test_resolved_values() {
+        let settings = Settings::parse(CONFIG, Profile::Default).unwrap();
+        assert_eq!(settings.theme, DEFAULT_THEME);
+        assert_eq!(settings.cursor, "bar");
+        assert_eq!(settings.time.get(), 45);
+        assert_eq!(settings.mode_tokens(), vec!["uppercase", "punctuation"]);
+        assert_eq!(settings.modes.numbers_chance, 0.5);
+        assert_eq!(settings.profiles, vec!["code"]);
+        assert_ne!(settings.keymap, Keymap::default());
+
+        let code = Settings::parse(CONFIG, Profile::Named("code")).unwrap();
+        assert_eq!(code.profile.as_deref(), Some("code"));
+        assert_eq!(code.time.get(), 90);
+        assert_eq!(code.cursor, "bar");
+    }
+
+    #[test]
+    fn test_invalid_keybindings_use_the_defaults() {
+        for bindings in ["quick = \"nope+x\"", "stats = \"s\"", "fly = \"f\""] {
+            let text = format!("[keybindings]\n{bindings}\n");
+            let settings = Settings::parse(&text, Profile::Default).unwrap();
+            assert_eq!(settings.keymap, Keymap::default(), "{bindings}");
+        }
+    }
+}
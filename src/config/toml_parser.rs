@@ -1,85 +1,286 @@
-use dirs::home_dir;
-use lazy_static::lazy_static;
-use serde::{Deserialize, Serialize};
+use anyhow::{bail, Context, Result};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
-use std::sync::Mutex;
-use toml;
-
-#[derive(Serialize, Deserialize, Clone)]
-pub struct ModesTable {
-    pub default_mode: Option<String>,
-    pub uppercase_chance: Option<String>,
-    pub punctuation_chance: Option<String>,
-}
+use std::path::Path;
+use toml::{self, Table, Value};
+
+use super::schema::{self, KEYS};
+use crate::paths;
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct LanguageTable {
-    pub lang: Option<String>,
+/// Which `[profiles.<name>]` table to apply on top of the base settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile<'a> {
+    /// The one named by the top-level `profile` key, if it exists.
+    Default,
+    Named(&'a str),
+    /// Only the base settings.
+    Base,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
-pub struct GameTable {
-    pub time: Option<u64>,
+impl<'a> Profile<'a> {
+    /// The profile called `name`, or only the base settings for `None`.
+    pub fn of(name: Option<&'a str>) -> Self {
+        match name {
+            Some(name) => Profile::Named(name),
+            None => Profile::Base,
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+/// Keys that only mean something at the top level of the file.
+pub const TOP_LEVEL_ONLY: &[&str] = &["version", "profile", "profiles"];
+
+/// A parsed config file with its profile applied. Values are read by
+/// their dotted key from [`KEYS`], which also decides what loads.
+#[derive(Default)]
 pub struct ConfigToml {
-    theme: Option<String>,
-    cursor: Option<String>,
-    modes: Option<ModesTable>,
-    language: Option<LanguageTable>,
-    game: Option<GameTable>,
+    root: Table,
+    /// The profile applied to the settings above.
+    active: Option<String>,
 }
 
 impl ConfigToml {
     pub fn new() -> Self {
-        let mut config_filepaths: Vec<PathBuf> = vec![PathBuf::from("./config.toml")];
+        ConfigToml::load(Profile::Default).unwrap_or_default()
+    }
 
-        if let Some(home_path) = home_dir() {
-            config_filepaths.push(home_path.join(".config/typy/config.toml"));
+    /// Reads the config file with `profile` applied; an empty config if
+    /// there is no file.
+    pub fn load(profile: Profile) -> Result<Self> {
+        match paths::config_file() {
+            Some(path) => ConfigToml::read(&path, profile),
+            None => ConfigToml::parse("", profile),
         }
+    }
 
-        let mut content = "".to_owned();
+    /// Reads the config at `path` with `profile` applied; an empty config
+    /// if the file does not exist.
+    pub fn read(path: &Path, profile: Profile) -> Result<Self> {
+        let content = fs::read_to_string(path).unwrap_or_default();
+        ConfigToml::parse(&content, profile)
+    }
 
-        for filepath in config_filepaths {
-            let result = fs::read_to_string(filepath);
+    /// Parses `text`, merging the chosen profile's tables into the base
+    /// settings key by key.
+    pub fn parse(text: &str, profile: Profile) -> Result<Self> {
+        let mut root: Table = toml::from_str(text).context("Failed to parse config file")?;
+        let profiles = root.get("profiles").and_then(|p| p.as_table());
+        let find = |name: &str| profiles.and_then(|p| p.get(name)?.as_table()).cloned();
 
-            if result.is_ok() {
-                content = result.unwrap();
-                break;
-            }
-        }
-        let config_toml: ConfigToml =
-            toml::from_str(&content).unwrap_or_else(|_| ConfigToml::default());
-        config_toml
+        let chosen = match profile {
+            Profile::Named(name) => match find(name) {
+                Some(table) => Some((name.to_string(), table)),
+                None => bail!(
+                    "Unknown profile `{name}`; the config file defines {}",
+                    names_label(profiles)
+                ),
+            },
+            Profile::Default => root
+                .get("profile")
+                .and_then(|p| p.as_str())
+                .and_then(|name| Some((name.to_string(), find(name)?))),
+            Profile::Base => None,
+        };
+
+        let active = chosen.map(|(name, table)| {
+            merge(&mut root, table);
+            name
+        });
+        check_loads(&root).context("Invalid config file")?;
+        Ok(ConfigToml { root, active })
     }
 
-    pub fn get_theme(&self) -> Option<String> {
-        self.theme.clone()
+    /// The profile in effect, if any.
+    pub fn active_profile(&self) -> Option<String> {
+        self.active.clone()
     }
 
-    pub fn get_cursor(&self) -> Option<String> {
-        self.cursor.clone()
+    /// The names of all profiles, sorted.
+    pub fn profile_names(&self) -> Vec<String> {
+        self.root
+            .get("profiles")
+            .and_then(Value::as_table)
+            .map(|p| p.keys().cloned().collect())
+            .unwrap_or_default()
     }
 
-    pub fn get_modes(&self) -> Option<ModesTable> {
-        self.modes.clone()
+    /// The value of the dotted `key` from [`KEYS`], e.g. `game.time`;
+    /// `None` if it is not set or doesn't fit `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let spec = schema::find_path(key).expect("key is not in the schema");
+        let table = match spec.table {
+            Some(table) => self.root.get(table)?.as_table()?,
+            None => &self.root,
+        };
+        table.get(spec.name)?.clone().try_into().ok()
     }
 
-    pub fn get_language(&self) -> Option<LanguageTable> {
-        self.language.clone()
+    /// The `[keybindings]` table: chords by action name.
+    pub fn keybindings(&self) -> BTreeMap<String, Vec<String>> {
+        let Some(table) = self.root.get("keybindings").and_then(Value::as_table) else {
+            return BTreeMap::new();
+        };
+        table
+            .iter()
+            .filter_map(|(action, keys)| Some((action.clone(), chords(keys)?)))
+            .collect()
     }
+}
 
-    pub fn get_game(&self) -> Option<GameTable> {
-        self.game.clone()
+/// A key binding: one chord or a list of alternatives.
+fn chords(keys: &Value) -> Option<Vec<String>> {
+    match keys {
+        Value::String(chord) => Some(vec![chord.clone()]),
+        Value::Array(chords) => chords
+            .iter()
+            .map(|c| c.as_str().map(str::to_string))
+            .collect(),
+        _ => None,
     }
 }
 
-lazy_static! {
-    static ref CONFIG: Mutex<ConfigToml> = Mutex::new(ConfigToml::new());
+/// Fails on values typy can't read, which make it ignore the file.
+fn check_loads(root: &Table) -> Result<()> {
+    for spec in KEYS {
+        let table = match spec.table {
+            Some(name) => match root.get(name) {
+                Some(Value::Table(table)) => table,
+                Some(other) => bail!("`{name}` must be a table, found {}", other.type_str()),
+                None => continue,
+            },
+            None => root,
+        };
+        match table.get(spec.name) {
+            Some(value) if !spec.kind.loads(value) => bail!(
+                "`{}` must be {}, found {}",
+                spec.path(),
+                spec.kind.expected(),
+                value.type_str()
+            ),
+            _ => {}
+        }
+    }
+    if let Some(profiles) = root.get("profiles") {
+        let all_tables = profiles
+            .as_table()
+            .is_some_and(|p| p.values().all(Value::is_table));
+        if !all_tables {
+            bail!("`profiles` must hold a table for each profile");
+        }
+    }
+    match root.get("keybindings") {
+        Some(Value::Table(table)) => {
+            if let Some(action) = table.iter().find(|(_, keys)| chords(keys).is_none()) {
+                bail!("`keybindings.{}` must be a key or a list of keys", action.0);
+            }
+        }
+        Some(other) => bail!("`keybindings` must be a table, found {}", other.type_str()),
+        None => {}
+    }
+    Ok(())
 }
 
-pub fn get_config() -> &'static Mutex<ConfigToml> {
-    &CONFIG
+/// Overrides the values in `base` with those in `profile`, descending into
+/// tables so a profile can change one key of `[game]` and keep the rest.
+fn merge(base: &mut Table, profile: Table) {
+    for (key, value) in profile {
+        if TOP_LEVEL_ONLY.contains(&key.as_str()) {
+            continue;
+        }
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(value)) => merge(base, value),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+fn names_label(profiles: Option<&Table>) -> String {
+    match profiles.filter(|p| !p.is_empty()) {
+        Some(p) => p
+            .keys()
+            .map(|name| format!("`{name}`"))
+            .collect::<Vec<_>>()
+            .join(", "),
+        None => "no profiles".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod toml_parser_tests {
+    use super::*;
+    use crate::config::model::Seconds;
+
+    const CONFIG: &str = r#"
+theme = "nord"
+profile = "warmup"
+
+[game]
+time = 60
+afk_timeout = 5
+
+[profiles.warmup]
+game = { time = 15 }
+
+[profiles.code]
+theme = "dracula"
+version = 9
+[profiles.code.language]
+lang = "rust"
+"#;
+
+    #[test]
+    fn test_profiles_override_key_by_key() {
+        let config = ConfigToml::parse(CONFIG, Profile::Default).unwrap();
+        assert_eq!(config.active_profile().as_deref(), Some("warmup"));
+        let time = config.get::<Seconds>("game.time");
+        assert_eq!(time.map(Seconds::get), Some(15));
+        assert_eq!(config.get::<u64>("game.afk_timeout"), Some(5));
+        assert_eq!(config.get::<String>("theme").as_deref(), Some("nord"));
+
+        let code = ConfigToml::parse(CONFIG, Profile::Named("code")).unwrap();
+        assert_eq!(code.get::<String>("theme").as_deref(), Some("dracula"));
+        assert_eq!(code.get::<String>("language.lang").as_deref(), Some("rust"));
+        assert_eq!(code.get::<Seconds>("game.time").map(Seconds::get), Some(60));
+        assert_eq!(code.get::<i64>("version"), None);
+        assert_eq!(code.profile_names(), vec!["code", "warmup"]);
+
+        let base = ConfigToml::parse(CONFIG, Profile::Base).unwrap();
+        assert_eq!(base.active_profile(), None);
+        assert_eq!(base.get::<Seconds>("game.time").map(Seconds::get), Some(60));
+    }
+
+    #[test]
+    fn test_keys_load_by_kind() {
+        let wrong = |text: &str| {
+            let err = ConfigToml::parse(text, Profile::Base).err().unwrap();
+            format!("{:#}", err)
+        };
+        assert_eq!(
+            wrong("[game]\ntime = \"long\""),
+            "Invalid config file: `game.time` must be a whole number from 1 to 3600, found string"
+        );
+        assert!(wrong("game = 3").contains("`game` must be a table"));
+        assert!(wrong("[keybindings]\nrestart = 3").contains("keybindings"));
+
+        let lenient = "[modes]\nuppercase_chance = \"0.5\"\n[language]\nlang = \"x\"";
+        let config = ConfigToml::parse(lenient, Profile::Base).unwrap();
+        assert_eq!(config.get::<String>("language.lang").as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn test_unknown_profile() {
+        let err = ConfigToml::parse(CONFIG, Profile::Named("german"))
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "Unknown profile `german`; the config file defines `code`, `warmup`"
+        );
+        let missing = CONFIG.replace("profile = \"warmup\"", "profile = \"german\"");
+        let config = ConfigToml::parse(&missing, Profile::Default).unwrap();
+        assert_eq!(config.active_profile(), None);
+    }
 }
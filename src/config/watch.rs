@@ -0,0 +1,97 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::paths;
+
+/// When a file was last written and how long it is; `None` if it is missing.
+type Stamp = Option<(Option<SystemTime>, u64)>;
+
+fn stamp(path: &Path) -> Stamp {
+    fs::metadata(path)
+        .ok()
+        .map(|m| (m.modified().ok(), m.len()))
+}
+
+/// Which of the watched files changed since the last poll.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Changes {
+    pub config: bool,
+    pub theme: bool,
+}
+
+/// Notices edits to the config file and `theme.toml` by polling their
+/// modification times, so typy can pick them up without a restart.
+pub struct Watcher {
+    config: (PathBuf, Stamp),
+    theme: Option<(PathBuf, Stamp)>,
+}
+
+impl Watcher {
+    /// Watches the config file typy reads and writes, which need not exist
+    /// yet, and `theme.toml`.
+    pub fn new() -> Option<Self> {
+        let config = paths::config_file()?;
+        let theme = paths::config_dir().map(|d| d.join("theme.toml"));
+        Some(Watcher::with_paths(config, theme))
+    }
+
+    pub fn with_paths(config: PathBuf, theme: Option<PathBuf>) -> Self {
+        Watcher {
+            config: (config.clone(), stamp(&config)),
+            theme: theme.map(|t| (t.clone(), stamp(&t))),
+        }
+    }
+
+    /// Compares the files with what they looked like at the last poll.
+    pub fn poll(&mut self) -> Changes {
+        let changed = |(path, last): &mut (PathBuf, Stamp)| {
+            let now = stamp(path);
+            let changed = now != *last;
+            *last = now;
+            changed
+        };
+        Changes {
+            config: changed(&mut self.config),
+            theme: self.theme.as_mut().is_some_and(changed),
+        }
+    }
+
+    /// Forgets changes typy made itself, e.g. by saving a setting.
+    pub fn sync(&mut self) {
+        self.poll();
+    }
+}
+
+#[cfg(test)]
+mod watch_tests {
+    use super::*;
+
+    #[test]
+    fn test_poll() {
+        let dir = std::env::temp_dir().join(format!("typy-watch-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let (config, theme) = (dir.join("config.toml"), dir.join("theme.toml"));
+        fs::write(&config, "theme = \"nord\"\n").unwrap();
+        let _ = fs::remove_file(&theme);
+
+        let mut watcher = Watcher::with_paths(config.clone(), Some(theme.clone()));
+        assert_eq!(watcher.poll(), Changes::default());
+
+        fs::write(&config, "theme = \"Tokyo Night\"\n").unwrap();
+        let changes = watcher.poll();
+        assert!(changes.config && !changes.theme);
+        assert_eq!(watcher.poll(), Changes::default());
+
+        // creating a file counts as a change, and so does removing it
+        fs::write(&theme, "[mine]\nfg = \"#ffffff\"\n").unwrap();
+        assert!(watcher.poll().theme);
+        fs::remove_file(&theme).unwrap();
+        assert!(watcher.poll().theme);
+
+        fs::write(&config, "cursor = \"bar\"\n").unwrap();
+        watcher.sync();
+        assert_eq!(watcher.poll(), Changes::default());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,230 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        KeyChord { code, modifiers }
+    }
+
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let event = KeyChord::from(*key);
+        self.code == event.code && self.modifiers == event.modifiers
+    }
+
+    /// Whether this chord would produce text on the typing screen.
+    pub fn is_text(&self) -> bool {
+        matches!(self.code, KeyCode::Char(_)) && self.modifiers.is_empty()
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(key: KeyEvent) -> Self {
+        let mut modifiers = key.modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT);
+        let code = match key.code {
+            KeyCode::BackTab => KeyCode::BackTab,
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            code => {
+                if !matches!(code, KeyCode::Char(_)) {
+                    modifiers |= key.modifiers & KeyModifiers::SHIFT;
+                }
+                code
+            }
+        };
+        KeyChord { code, modifiers }
+    }
+}
+
+impl FromStr for KeyChord {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let input = input.trim();
+        if input.chars().count() == 1 {
+            let c = input.chars().next().unwrap_or(' ');
+            return Ok(KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+
+        let mut parts: Vec<&str> = input.split('+').map(str::trim).collect();
+        let Some(key) = parts.pop().filter(|k| !k.is_empty()) else {
+            bail!("Missing key in chord `{input}`");
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for part in parts {
+            match part.to_lowercase().as_str() {
+                "ctrl" | "control" => modifiers |= KeyModifiers::CONTROL,
+                "alt" | "meta" => modifiers |= KeyModifiers::ALT,
+                "shift" => modifiers |= KeyModifiers::SHIFT,
+                other => bail!("Unknown modifier `{other}` in chord `{input}`"),
+            }
+        }
+
+        let code = parse_key(key)
+            .ok_or_else(|| anyhow::anyhow!("Unknown key `{key}` in chord `{input}`"))?;
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                if modifiers.contains(KeyModifiers::CONTROL) {
+                    KeyCode::Char(c)
+                } else {
+                    KeyCode::Char(c.to_uppercase().next().unwrap_or(c))
+                }
+            }
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            KeyCode::Tab if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::BackTab
+            }
+            code => code,
+        };
+
+        Ok(KeyChord { code, modifiers })
+    }
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    if key.chars().count() == 1 {
+        return key.chars().next().map(KeyCode::Char);
+    }
+    let lower = key.to_lowercase();
+    let code = match lower.as_str() {
+        "enter" | "return" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "backtab" => KeyCode::BackTab,
+        "space" => KeyCode::Char(' '),
+        "plus" => KeyCode::Char('+'),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        "insert" | "ins" => KeyCode::Insert,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        f if f.starts_with('f') => {
+            KeyCode::F(f[1..].parse().ok().filter(|n| (1..=24).contains(n))?)
+        }
+        _ => return None,
+    };
+    Some(code)
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Enter => write!(f, "enter"),
+            KeyCode::Esc => write!(f, "esc"),
+            KeyCode::Tab => write!(f, "tab"),
+            KeyCode::BackTab => write!(f, "shift+tab"),
+            KeyCode::Backspace => write!(f, "backspace"),
+            KeyCode::Delete => write!(f, "delete"),
+            KeyCode::Insert => write!(f, "insert"),
+            KeyCode::Up => write!(f, "up"),
+            KeyCode::Down => write!(f, "down"),
+            KeyCode::Left => write!(f, "left"),
+            KeyCode::Right => write!(f, "right"),
+            KeyCode::Home => write!(f, "home"),
+            KeyCode::End => write!(f, "end"),
+            KeyCode::PageUp => write!(f, "pageup"),
+            KeyCode::PageDown => write!(f, "pagedown"),
+            KeyCode::F(n) => write!(f, "f{n}"),
+            _ => write!(f, "?"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod chord_tests {
+    use super::*;
+
+    fn event(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent::new(code, modifiers)
+    }
+
+    #[test]
+    fn test_parse_plain_and_named() {
+        let q: KeyChord = "q".parse().unwrap();
+        assert_eq!(q, KeyChord::new(KeyCode::Char('q'), KeyModifiers::NONE));
+        let enter: KeyChord = "Enter".parse().unwrap();
+        assert_eq!(enter.code, KeyCode::Enter);
+        let space: KeyChord = "space".parse().unwrap();
+        assert_eq!(space.code, KeyCode::Char(' '));
+        let f1: KeyChord = "f1".parse().unwrap();
+        assert_eq!(f1.code, KeyCode::F(1));
+        let question: KeyChord = "?".parse().unwrap();
+        assert_eq!(question.code, KeyCode::Char('?'));
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        let chord: KeyChord = "ctrl+R".parse().unwrap();
+        assert_eq!(
+            chord,
+            KeyChord::new(KeyCode::Char('r'), KeyModifiers::CONTROL)
+        );
+        let chord: KeyChord = "shift+r".parse().unwrap();
+        assert_eq!(chord, KeyChord::new(KeyCode::Char('R'), KeyModifiers::NONE));
+        let chord: KeyChord = "shift+tab".parse().unwrap();
+        assert_eq!(chord.code, KeyCode::BackTab);
+        let chord: KeyChord = "alt+shift+enter".parse().unwrap();
+        assert_eq!(chord.modifiers, KeyModifiers::ALT | KeyModifiers::SHIFT);
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!("hyper+a".parse::<KeyChord>().is_err());
+        assert!("ctrl+".parse::<KeyChord>().is_err());
+        assert!("notakey".parse::<KeyChord>().is_err());
+        assert!("f99".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_matches_events() {
+        let chord: KeyChord = "ctrl+p".parse().unwrap();
+        assert!(chord.matches(&event(KeyCode::Char('p'), KeyModifiers::CONTROL)));
+        assert!(!chord.matches(&event(KeyCode::Char('p'), KeyModifiers::NONE)));
+
+        let upper: KeyChord = "R".parse().unwrap();
+        assert!(upper.matches(&event(KeyCode::Char('R'), KeyModifiers::SHIFT)));
+
+        let question: KeyChord = "?".parse().unwrap();
+        assert!(question.matches(&event(KeyCode::Char('?'), KeyModifiers::SHIFT)));
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for text in ["q", "ctrl+r", "enter", "space", "alt+x", "f5", "shift+tab"] {
+            let chord: KeyChord = text.parse().unwrap();
+            assert_eq!(chord.to_string(), text);
+            assert_eq!(chord.to_string().parse::<KeyChord>().unwrap(), chord);
+        }
+    }
+}
@@ -0,0 +1,429 @@
+mod chord;
+
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
+
+use anyhow::{bail, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+pub use chord::KeyChord;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Action {
+    Start,
+    Restart,
+    Repeat,
+    Quick,
+    Settings,
+    Stats,
+    Help,
+    Palette,
+    Back,
+    Quit,
+    Up,
+    Down,
+    Select,
+    Close,
+    Filter,
+}
+
+impl Action {
+    pub const ALL: &'static [Action] = &[
+        Action::Start,
+        Action::Restart,
+        Action::Repeat,
+        Action::Quick,
+        Action::Settings,
+        Action::Stats,
+        Action::Help,
+        Action::Palette,
+        Action::Back,
+        Action::Quit,
+        Action::Up,
+        Action::Down,
+        Action::Select,
+        Action::Close,
+        Action::Filter,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Start => "start",
+            Action::Restart => "restart",
+            Action::Repeat => "repeat",
+            Action::Quick => "quick",
+            Action::Settings => "settings",
+            Action::Stats => "stats",
+            Action::Help => "help",
+            Action::Palette => "palette",
+            Action::Back => "back",
+            Action::Quit => "quit",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::Select => "select",
+            Action::Close => "close",
+            Action::Filter => "filter",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Start => "start a test",
+            Action::Restart => "restart with new words",
+            Action::Repeat => "repeat the same words",
+            Action::Quick => "open the quick menu",
+            Action::Settings => "open settings",
+            Action::Stats => "open stats",
+            Action::Help => "show keybindings",
+            Action::Palette => "open the command palette",
+            Action::Back => "go back",
+            Action::Quit => "quit",
+            Action::Up => "move up",
+            Action::Down => "move down",
+            Action::Select => "open / confirm",
+            Action::Close => "close a dropdown",
+            Action::Filter => "filter by profile",
+        }
+    }
+
+    /// Short label used in the hint bar at the bottom of each screen.
+    pub fn hint(&self) -> &'static str {
+        match self {
+            Action::Palette => "commands",
+            Action::Down => "move",
+            _ => self.name(),
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.iter().copied().find(|a| a.name() == name)
+    }
+
+    fn defaults(&self) -> &'static [&'static str] {
+        match self {
+            Action::Start => &["enter"],
+            Action::Restart => &["enter"],
+            Action::Repeat => &["r"],
+            Action::Quick => &["tab"],
+            Action::Settings => &["s"],
+            Action::Stats => &["p"],
+            Action::Help => &["?", "f1"],
+            Action::Palette => &[":", "ctrl+p"],
+            Action::Back => &["esc", "q"],
+            Action::Quit => &["q", "esc"],
+            Action::Up => &["k", "up"],
+            Action::Down => &["j", "down"],
+            Action::Select => &["enter", "l", "space"],
+            Action::Close => &["h", "left"],
+            Action::Filter => &["f"],
+        }
+    }
+}
+
+/// Where a key press is handled; each context resolves its own set of actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    Home,
+    Typing,
+    Quick,
+    Results,
+    Settings,
+    Stats,
+}
+
+/// The keys that pick a time, then a mode, on the quick menu.
+pub const QUICK_DIGITS: RangeInclusive<char> = '1'..='9';
+
+impl Context {
+    pub const ALL: &'static [Context] = &[
+        Context::Home,
+        Context::Typing,
+        Context::Quick,
+        Context::Results,
+        Context::Settings,
+        Context::Stats,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Context::Home => "home",
+            Context::Typing => "typing",
+            Context::Quick => "quick menu",
+            Context::Results => "results",
+            Context::Settings => "settings",
+            Context::Stats => "stats",
+        }
+    }
+
+    pub fn actions(&self) -> &'static [Action] {
+        match self {
+            Context::Home => &[
+                Action::Start,
+                Action::Quick,
+                Action::Settings,
+                Action::Stats,
+                Action::Palette,
+                Action::Help,
+                Action::Quit,
+            ],
+            Context::Typing => &[Action::Quick, Action::Palette, Action::Help, Action::Back],
+            Context::Quick => &[Action::Restart, Action::Repeat, Action::Quick, Action::Back],
+            Context::Results => &[
+                Action::Restart,
+                Action::Repeat,
+                Action::Palette,
+                Action::Help,
+                Action::Back,
+            ],
+            Context::Settings => &[
+                Action::Up,
+                Action::Down,
+                Action::Select,
+                Action::Close,
+                Action::Palette,
+                Action::Help,
+                Action::Back,
+            ],
+            Context::Stats => &[
+                Action::Stats,
+                Action::Filter,
+                Action::Palette,
+                Action::Help,
+                Action::Back,
+            ],
+        }
+    }
+
+    /// Keys the context handles itself, which no action may take.
+    pub fn reserved(&self) -> Vec<KeyChord> {
+        match self {
+            Context::Quick => QUICK_DIGITS
+                .map(|c| KeyChord::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// The actions advertised in the hint bar of this context.
+    pub fn hints(&self) -> &'static [Action] {
+        match self {
+            Context::Home => &[
+                Action::Settings,
+                Action::Stats,
+                Action::Quick,
+                Action::Palette,
+                Action::Help,
+                Action::Quit,
+            ],
+            Context::Typing => &[Action::Quick, Action::Back],
+            Context::Quick => &[Action::Back],
+            Context::Results => &[Action::Restart, Action::Repeat, Action::Back],
+            Context::Settings => &[Action::Down, Action::Select, Action::Back],
+            Context::Stats => &[Action::Filter, Action::Back, Action::Help],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: BTreeMap<Action, Vec<KeyChord>>,
+}
+
+impl Keymap {
+    /// Builds a keymap from `action -> chords` overrides on top of the defaults.
+    pub fn with_overrides(overrides: &BTreeMap<String, Vec<String>>) -> Result<Self> {
+        let mut keymap = Keymap::default();
+        for (name, chords) in overrides {
+            let Some(action) = Action::from_name(name) else {
+                bail!("Unknown action `{name}` in [keybindings]");
+            };
+            let parsed = chords
+                .iter()
+                .map(|c| c.parse::<KeyChord>())
+                .collect::<Result<Vec<_>>>()?;
+            keymap.bindings.insert(action, parsed);
+        }
+        keymap.check_conflicts()?;
+        Ok(keymap)
+    }
+
+    fn check_conflicts(&self) -> Result<()> {
+        for context in Context::ALL {
+            let actions = context.actions();
+            for (i, a) in actions.iter().enumerate() {
+                for b in &actions[i + 1..] {
+                    if let Some(chord) = self.keys(*a).iter().find(|c| self.keys(*b).contains(c)) {
+                        bail!(
+                            "`{chord}` is bound to both `{}` and `{}` on the {} screen",
+                            a.name(),
+                            b.name(),
+                            context.name()
+                        );
+                    }
+                }
+            }
+            let reserved = context.reserved();
+            for action in actions {
+                if let Some(chord) = self.keys(*action).iter().find(|c| reserved.contains(c)) {
+                    bail!(
+                        "`{chord}` is bound to `{}` but is reserved on the {} screen",
+                        action.name(),
+                        context.name()
+                    );
+                }
+            }
+        }
+        // Text keys type on the typing screen; only the defaults keep theirs,
+        // e.g. `q` for `back`, which works on the other screens.
+        let defaults = Keymap::default();
+        for action in Context::Typing.actions() {
+            let default = defaults.keys(*action);
+            let typed = |c: &&KeyChord| c.is_text() && !default.contains(c);
+            if let Some(chord) = self.keys(*action).iter().find(typed) {
+                bail!(
+                    "`{chord}` is bound to `{}` but types text on the typing screen",
+                    action.name()
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn keys(&self, action: Action) -> &[KeyChord] {
+        self.bindings.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// The first chord bound to `action`, for compact hints.
+    pub fn label(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Every chord bound to `action`, joined for the help overlay.
+    pub fn labels(&self, action: Action) -> String {
+        let keys: Vec<String> = self.keys(action).iter().map(|c| c.to_string()).collect();
+        if keys.is_empty() {
+            "unbound".to_string()
+        } else {
+            keys.join(" / ")
+        }
+    }
+
+    /// Resolves a key press to an action in `context`. On the typing screen
+    /// chords that produce text are never treated as actions.
+    pub fn action(&self, context: Context, key: &KeyEvent) -> Option<Action> {
+        context.actions().iter().copied().find(|action| {
+            self.keys(*action)
+                .iter()
+                .any(|chord| chord.matches(key) && !(context == Context::Typing && chord.is_text()))
+        })
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = Action::ALL
+            .iter()
+            .map(|action| {
+                let chords = action
+                    .defaults()
+                    .iter()
+                    .filter_map(|c| c.parse().ok())
+                    .collect();
+                (*action, chords)
+            })
+            .collect();
+        Keymap { bindings }
+    }
+}
+
+#[cfg(test)]
+mod keymap_tests {
+    use super::*;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn overrides(pairs: &[(&str, &[&str])]) -> BTreeMap<String, Vec<String>> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.iter().map(|s| s.to_string()).collect()))
+            .collect()
+    }
+
+    fn press(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_defaults_have_no_conflicts() {
+        assert!(Keymap::default().check_conflicts().is_ok());
+    }
+
+    #[test]
+    fn test_resolve_per_context() {
+        let keymap = Keymap::default();
+        let enter = press(KeyCode::Enter);
+        assert_eq!(keymap.action(Context::Home, &enter), Some(Action::Start));
+        assert_eq!(
+            keymap.action(Context::Results, &enter),
+            Some(Action::Restart)
+        );
+        assert_eq!(
+            keymap.action(Context::Settings, &enter),
+            Some(Action::Select)
+        );
+        assert_eq!(keymap.action(Context::Typing, &enter), None);
+    }
+
+    #[test]
+    fn test_text_keys_ignored_while_typing() {
+        let keymap = Keymap::default();
+        assert_eq!(
+            keymap.action(Context::Typing, &press(KeyCode::Char('q'))),
+            None
+        );
+        assert_eq!(
+            keymap.action(Context::Typing, &press(KeyCode::Esc)),
+            Some(Action::Back)
+        );
+        assert_eq!(
+            keymap.action(Context::Stats, &press(KeyCode::Char('q'))),
+            Some(Action::Back)
+        );
+
+        // a text key of its own would never reach the action while typing
+        let err = Keymap::with_overrides(&overrides(&[("back", &["x", "esc"])])).unwrap_err();
+        assert!(err.to_string().contains("types text on the typing screen"));
+        assert!(Keymap::with_overrides(&overrides(&[("filter", &["x"])])).is_ok());
+    }
+
+    #[test]
+    fn test_override_replaces_defaults() {
+        let keymap = Keymap::with_overrides(&overrides(&[("settings", &["ctrl+s"])])).unwrap();
+        assert_eq!(
+            keymap.action(Context::Home, &press(KeyCode::Char('s'))),
+            None
+        );
+        let ctrl_s = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        assert_eq!(
+            keymap.action(Context::Home, &ctrl_s),
+            Some(Action::Settings)
+        );
+    }
+
+    #[test]
+    fn test_conflict_detected() {
+        let err = Keymap::with_overrides(&overrides(&[("stats", &["s"])])).unwrap_err();
+        assert!(err.to_string().contains("`s` is bound to both"));
+        let err = Keymap::with_overrides(&overrides(&[("repeat", &["3"])])).unwrap_err();
+        assert!(err.to_string().contains("reserved on the quick menu"));
+        assert!(Keymap::with_overrides(&overrides(&[("stats", &["3"])])).is_ok());
+    }
+
+    #[test]
+    fn test_unknown_action_and_bad_chord() {
+        assert!(Keymap::with_overrides(&overrides(&[("fly", &["f"])])).is_err());
+        assert!(Keymap::with_overrides(&overrides(&[("quit", &["ctrl+nope"])])).is_err());
+    }
+}
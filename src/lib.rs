@@ -0,0 +1,54 @@
+//! The typing engine behind the `typy` terminal typing test.
+//!
+//! The binary is a thin consumer of this crate; the modules below are the
+//! reusable part. Nothing here reads the user's config file, so everything is
+//! configured through explicit values such as [`mode::ModeSettings`] and a
+//! [`clock::Clock`].
+//!
+//! ```
+//! use typy::clock::FakeClock;
+//! use typy::typing::TypingSession;
+//!
+//! let clock = FakeClock::new();
+//! let mut session = TypingSession::from_text("hello world", 30, clock.shared());
+//! for c in "hello".chars() {
+//!     session.type_char(c);
+//! }
+//! clock.advance_secs(6.0);
+//! session.space();
+//! for c in "world".chars() {
+//!     session.type_char(c);
+//! }
+//!
+//! assert!(session.is_finished());
+//! assert_eq!(session.stats.wpm().round(), 22.0);
+//! ```
+//!
+//! - [`typing`]: the typing session, its words and per-keystroke state.
+//! - [`word_provider`]: the [`word_provider::WordSource`] trait and its
+//!   providers: language lists, weighted lists, passages, fixed text.
+//! - [`mode`]: uppercase / punctuation / numbers transforms applied to word
+//!   lists.
+//! - [`scores`]: result statistics and the on-disk score history.
+//! - [`clock`]: the time source used by the engine.
+
+mod app;
+mod cli;
+pub mod clock;
+mod config;
+mod keymap;
+pub mod mode;
+mod palette;
+mod paths;
+pub mod scores;
+mod settings;
+mod simulate;
+mod theme;
+mod tui;
+pub mod typing;
+mod ui;
+pub mod word_provider;
+
+/// The `typy` command line; the binary's entry point.
+#[doc(hidden)]
+pub use cli::run;
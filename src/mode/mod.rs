@@ -1,3 +1,7 @@
 mod mode_selector;
+mod punctuation;
+mod settings;
 
 pub use mode_selector::{Mode, ModeType};
+pub(crate) use punctuation::{capitalise, end_sentence, PunctuationRules};
+pub use settings::ModeSettings;
@@ -2,13 +2,15 @@ use anyhow::Result;
 use rand::Rng;
 use std::str::FromStr;
 
-use crate::config::mode_settings::ModeSettings;
+use super::punctuation::{punctuate, PunctuationRules};
+use super::ModeSettings;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum ModeType {
     Normal,
     Uppercase,
     Punctuation,
+    Numbers,
 }
 
 impl ModeType {
@@ -17,6 +19,7 @@ impl ModeType {
             ModeType::Normal => "normal",
             ModeType::Uppercase => "uppercase",
             ModeType::Punctuation => "punctuation",
+            ModeType::Numbers => "numbers",
         }
     }
 }
@@ -28,6 +31,7 @@ impl FromStr for ModeType {
         match input {
             "uppercase" => Ok(ModeType::Uppercase),
             "punctuation" => Ok(ModeType::Punctuation),
+            "numbers" => Ok(ModeType::Numbers),
             "normal" => Ok(ModeType::Normal),
             _ => Err(()),
         }
@@ -39,18 +43,25 @@ pub struct Mode {
     modes: Vec<ModeType>,
     pub duration: u64,
     settings: ModeSettings,
+    punctuation: PunctuationRules,
 }
 
 impl Mode {
-    pub fn from_str(mode_strs: Vec<&str>) -> Result<Self> {
+    /// Parses mode names with the default chances.
+    pub(crate) fn from_str(mode_strs: Vec<&str>) -> Result<Self> {
+        Mode::with_settings(mode_strs, ModeSettings::default())
+    }
+
+    /// Parses mode names; with no names, `settings.default_modes` are used.
+    pub fn with_settings(mode_strs: Vec<&str>, settings: ModeSettings) -> Result<Self> {
         let mut modes = Vec::new();
-        let settings = ModeSettings::new();
 
         for mode_str in mode_strs {
             match mode_str {
                 "normal" => modes.push(ModeType::Normal),
                 "uppercase" => modes.push(ModeType::Uppercase),
                 "punctuation" => modes.push(ModeType::Punctuation),
+                "numbers" => modes.push(ModeType::Numbers),
                 _ => return Err(anyhow::anyhow!("Invalid mode: {}", mode_str)),
             }
         }
@@ -70,6 +81,7 @@ impl Mode {
             modes,
             duration: 0,
             settings,
+            punctuation: PunctuationRules::default(),
         })
     }
 
@@ -78,47 +90,78 @@ impl Mode {
         self
     }
 
-    pub fn transform(&self, list: &mut [Vec<String>]) {
+    /// Uses the punctuation conventions of `language`, e.g. French spacing
+    /// before `?` or Spanish `¿`.
+    pub fn add_language(mut self, language: &str) -> Self {
+        self.punctuation = PunctuationRules::for_language(language);
+        self
+    }
+
+    pub fn transform(&self, list: &mut Vec<String>) {
         let mut rng = rand::rng();
-        let punctuations = [".", ",", "!", "?", ";", ":", "-"];
+
+        // Numbers replace whole words, so they go first and can still get
+        // punctuation afterwards.
+        if self.modes.contains(&ModeType::Numbers) {
+            for item in list.iter_mut() {
+                if rng.random_bool(self.settings.numbers_chance.into()) {
+                    *item = number_token(&mut rng);
+                }
+            }
+        }
 
         for mode in &self.modes {
             match mode {
                 ModeType::Uppercase => {
-                    for sublist in list.iter_mut() {
-                        for item in sublist.iter_mut() {
-                            let mut new_item = String::new();
-                            for c in item.chars() {
-                                if rng.random_bool(self.settings.uppercase_chance.into()) {
-                                    new_item.push(c.to_uppercase().next().unwrap());
-                                } else {
-                                    new_item.push(c);
-                                }
+                    for item in list.iter_mut() {
+                        let mut new_item = String::new();
+                        for c in item.chars() {
+                            if rng.random_bool(self.settings.uppercase_chance.into()) {
+                                new_item.push(c.to_uppercase().next().unwrap());
+                            } else {
+                                new_item.push(c);
                             }
-                            *item = new_item;
                         }
+                        *item = new_item;
                     }
                 }
                 ModeType::Punctuation => {
-                    for sublist in list.iter_mut() {
-                        let len = sublist.len();
-                        if len > 1 {
-                            for item in sublist.iter_mut().take(len - 1) {
-                                if rng.random_bool(self.settings.punctuation_chance.into()) {
-                                    let punctuation =
-                                        punctuations[rng.random_range(0..punctuations.len())];
-                                    item.push_str(punctuation);
-                                }
-                            }
-                        }
-                    }
+                    *list = punctuate(
+                        std::mem::take(list),
+                        self.settings.punctuation_chance.into(),
+                        self.punctuation,
+                        &mut rng,
+                    );
                 }
-                ModeType::Normal => {}
+                ModeType::Numbers | ModeType::Normal => {}
             }
         }
     }
 }
 
+/// A random number as it shows up in text: a plain number, a year, a decimal
+/// or a phone-number-like group.
+fn number_token(rng: &mut impl Rng) -> String {
+    match rng.random_range(0..4) {
+        0 => rng.random_range(0..10_000).to_string(),
+        1 => rng.random_range(1900..=2030).to_string(),
+        2 => {
+            let places = rng.random_range(1..=2);
+            let fraction = rng.random_range(0..10_u32.pow(places));
+            format!(
+                "{}.{fraction:0width$}",
+                rng.random_range(0..1000),
+                width = places as usize
+            )
+        }
+        _ => format!(
+            "{:03}-{:04}",
+            rng.random_range(0..1000),
+            rng.random_range(0..10_000)
+        ),
+    }
+}
+
 #[cfg(test)]
 mod mode_tests {
     use super::*;
@@ -151,18 +194,60 @@ mod mode_tests {
     #[test]
     fn test_transform_uppercase() {
         let mode = Mode::from_str(vec!["uppercase"]).unwrap();
-        let mut list = vec![vec!["hello".to_string(), "world".to_string()]];
+        let mut list = vec!["hello".to_string(), "world".to_string()];
         mode.transform(&mut list);
-        assert_eq!(list.len(), 1);
-        assert_eq!(list[0].len(), 2);
+        assert_eq!(list.len(), 2);
     }
 
     #[test]
     fn test_transform_punctuation() {
         let mode = Mode::from_str(vec!["punctuation"]).unwrap();
-        let mut list = vec![vec!["hello".to_string(), "world".to_string()]];
+        let mut list = vec!["hello".to_string(), "world".to_string()];
+        mode.transform(&mut list);
+        assert_eq!(list.len(), 2);
+    }
+
+    #[test]
+    fn test_transform_punctuation_by_language() {
+        let settings = ModeSettings {
+            punctuation_chance: 1.0,
+            ..ModeSettings::default()
+        };
+        let mode = Mode::with_settings(vec!["punctuation"], settings)
+            .unwrap()
+            .add_language("french");
+        let mut list = vec!["bonjour".to_string(); 400];
+        mode.transform(&mut list);
+        assert!(list.len() > 400);
+        assert!(list[0].starts_with('B'));
+        assert!(list.iter().any(|w| w == "?" || w == "!"));
+    }
+
+    #[test]
+    fn test_transform_numbers() {
+        let settings = ModeSettings {
+            numbers_chance: 1.0,
+            ..ModeSettings::default()
+        };
+        let mode = Mode::with_settings(vec!["numbers"], settings).unwrap();
+        let mut list = vec!["hello".to_string(); 200];
         mode.transform(&mut list);
-        assert_eq!(list.len(), 1);
-        assert_eq!(list[0].len(), 2);
+        assert_eq!(list.len(), 200);
+        for token in &list {
+            assert!(token.chars().any(|c| c.is_ascii_digit()), "{token}");
+            assert!(token.chars().all(|c| c.is_ascii_digit() || c == '.' || c == '-'));
+        }
+    }
+
+    #[test]
+    fn test_number_token_shapes() {
+        let mut rng = rand::rng();
+        let tokens: Vec<String> = (0..400).map(|_| number_token(&mut rng)).collect();
+        assert!(tokens.iter().any(|t| t.contains('.')));
+        assert!(tokens.iter().any(|t| t.len() == 8 && t.as_bytes()[3] == b'-'));
+        assert!(tokens
+            .iter()
+            .filter_map(|t| t.parse::<u32>().ok())
+            .any(|n| (1900..=2030).contains(&n)));
     }
 }
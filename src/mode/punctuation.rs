@@ -0,0 +1,214 @@
+use rand::Rng;
+
+const SENTENCE_WORDS: std::ops::RangeInclusive<usize> = 4..=12;
+
+/// How a language punctuates sentences.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct PunctuationRules {
+    /// Marks typed after a space, as in French `vraiment ?`.
+    spaced: &'static [char],
+    /// Questions and exclamations also open with `¿` / `¡`, as in Spanish.
+    inverted: bool,
+}
+
+impl PunctuationRules {
+    /// Rules for a word list name; variants such as `french_1k` share the
+    /// rules of their base language.
+    pub(crate) fn for_language(language: &str) -> Self {
+        let base = language.split(['_', '-']).next().unwrap_or(language);
+        match base.to_lowercase().as_str() {
+            "french" => PunctuationRules {
+                spaced: &['?', '!', ':', ';'],
+                inverted: false,
+            },
+            "spanish" => PunctuationRules {
+                spaced: &[],
+                inverted: true,
+            },
+            _ => PunctuationRules::default(),
+        }
+    }
+}
+
+/// Groups `words` into sentences: each starts with a capital letter and ends
+/// with a period, question or exclamation mark. `chance` controls how often
+/// commas, quotes and parentheses show up inside a sentence.
+pub(crate) fn punctuate(
+    words: Vec<String>,
+    chance: f64,
+    rules: PunctuationRules,
+    rng: &mut impl Rng,
+) -> Vec<String> {
+    let chance = chance.clamp(0.0, 1.0);
+    let mut out = Vec::with_capacity(words.len() + words.len() / 8);
+    let mut words = words.into_iter().peekable();
+
+    while words.peek().is_some() {
+        let len = rng.random_range(SENTENCE_WORDS);
+        let sentence = words.by_ref().take(len).collect();
+        for word in punctuate_sentence(sentence, chance, rules, rng) {
+            match split_spaced(&word, rules) {
+                Some((word, mark)) => {
+                    out.push(word.to_string());
+                    out.push(mark.to_string());
+                }
+                None => out.push(word),
+            }
+        }
+    }
+    out
+}
+
+fn punctuate_sentence(
+    mut words: Vec<String>,
+    chance: f64,
+    rules: PunctuationRules,
+    rng: &mut impl Rng,
+) -> Vec<String> {
+    let last = words.len() - 1;
+    capitalise(&mut words[0]);
+
+    // An aside or a quotation, never covering the first word so it stays
+    // clear of the capital letter and any opening `¿`.
+    let mut wrapped = None;
+    if last > 0 && rng.random_bool(chance * 0.3) {
+        let start = rng.random_range(1..=last);
+        let end = (start + rng.random_range(0..3)).min(last);
+        let (open, close) = if rng.random_bool(0.5) {
+            ('"', '"')
+        } else {
+            ('(', ')')
+        };
+        words[start].insert(0, open);
+        words[end].push(close);
+        wrapped = Some(start..end);
+    }
+
+    // Pauses between clauses, at least two words apart and never inside
+    // the aside.
+    let mut since_pause = 0;
+    for (i, word) in words.iter_mut().enumerate().take(last) {
+        since_pause += 1;
+        let inside = wrapped.as_ref().is_some_and(|r| r.contains(&i));
+        if since_pause > 1 && !inside && rng.random_bool(chance * 0.25) {
+            word.push(match rng.random_range(0..20) {
+                0 => ';',
+                1 => ':',
+                _ => ',',
+            });
+            since_pause = 0;
+        }
+    }
+
+    let end = match rng.random_range(0..20) {
+        0..=2 => '?',
+        3 | 4 => '!',
+        _ => '.',
+    };
+    end_sentence(&mut words, 0, end, rules);
+    words
+}
+
+/// Closes the sentence that starts at `words[start]` with `end` the way
+/// `rules` write it, e.g. `¿…?` in Spanish or `… ?` in French.
+pub(crate) fn end_sentence(
+    words: &mut Vec<String>,
+    start: usize,
+    end: char,
+    rules: PunctuationRules,
+) {
+    if rules.inverted {
+        match end {
+            '?' => words[start].insert(0, '¿'),
+            '!' => words[start].insert(0, '¡'),
+            _ => {}
+        }
+    }
+    if rules.spaced.contains(&end) {
+        words.push(end.to_string());
+    } else if let Some(last) = words.last_mut() {
+        last.push(end);
+    }
+}
+
+pub(crate) fn capitalise(word: &mut String) {
+    let mut chars = word.chars();
+    if let Some(first) = chars.next() {
+        *word = first.to_uppercase().chain(chars).collect();
+    }
+}
+
+/// Splits a trailing mark that `rules` want typed after a space.
+fn split_spaced(word: &str, rules: PunctuationRules) -> Option<(&str, &str)> {
+    let mark = word.chars().last()?;
+    let at = word.len() - mark.len_utf8();
+    (at > 0 && rules.spaced.contains(&mark)).then(|| word.split_at(at))
+}
+
+#[cfg(test)]
+mod punctuation_tests {
+    use super::*;
+
+    fn words(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("w{i}")).collect()
+    }
+
+    #[test]
+    fn test_sentences_are_capitalised_and_closed() {
+        let mut rng = rand::rng();
+        let out = punctuate(words(300), 0.5, PunctuationRules::default(), &mut rng);
+        assert_eq!(out.len(), 300);
+        assert!(out[0].starts_with('W'));
+        assert!(out.last().unwrap().ends_with(['.', '?', '!']));
+
+        for pair in out.windows(2) {
+            let ends_sentence = pair[0].ends_with(['.', '?', '!']);
+            let starts_sentence = pair[1].trim_start_matches(['"', '(']).starts_with('W');
+            assert_eq!(ends_sentence, starts_sentence, "{pair:?}");
+        }
+    }
+
+    #[test]
+    fn test_no_inner_punctuation_without_chance() {
+        let mut rng = rand::rng();
+        let out = punctuate(words(200), 0.0, PunctuationRules::default(), &mut rng);
+        for word in &out {
+            assert!(!word.contains([',', ';', ':', '"', '(', ')']), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_quotes_and_parentheses_are_balanced() {
+        let mut rng = rand::rng();
+        let out = punctuate(words(2000), 1.0, PunctuationRules::default(), &mut rng);
+        let text = out.join(" ");
+        assert!(text.contains('('));
+        assert_eq!(text.matches('(').count(), text.matches(')').count());
+        assert_eq!(text.matches('"').count() % 2, 0);
+    }
+
+    #[test]
+    fn test_french_spaces_before_marks() {
+        let rules = PunctuationRules::for_language("french");
+        let mut rng = rand::rng();
+        let out = punctuate(words(2000), 1.0, rules, &mut rng);
+        assert!(out.iter().any(|w| w == "?"));
+        for word in &out {
+            assert!(
+                word.len() == 1 || !word.ends_with(['?', '!', ':', ';']),
+                "{word}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_spanish_inverted_marks() {
+        let rules = PunctuationRules::for_language("spanish_1k");
+        let mut rng = rand::rng();
+        let out = punctuate(words(2000), 0.0, rules, &mut rng);
+        let text = out.join(" ");
+        assert_eq!(text.matches('¿').count(), text.matches('?').count());
+        assert_eq!(text.matches('¡').count(), text.matches('!').count());
+        assert!(text.contains("¿W"));
+    }
+}
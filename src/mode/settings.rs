@@ -0,0 +1,20 @@
+use super::ModeType;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModeSettings {
+    pub default_modes: Vec<ModeType>,
+    pub uppercase_chance: f32,
+    pub punctuation_chance: f32,
+    pub numbers_chance: f32,
+}
+
+impl Default for ModeSettings {
+    fn default() -> Self {
+        ModeSettings {
+            default_modes: vec![ModeType::Normal],
+            uppercase_chance: 0.2,
+            punctuation_chance: 0.2,
+            numbers_chance: 0.2,
+        }
+    }
+}
@@ -0,0 +1,298 @@
+use crate::config::model::{Seconds, TIME_PRESETS};
+use crate::settings::{
+    mode_options, top_words_label, top_words_options, ACCENT_OPTIONS, CURSOR_OPTIONS, NO_PROFILE,
+};
+use crate::theme::available_themes;
+use crate::word_provider::{available_languages, TextStyle};
+
+const MAX_MATCHES: usize = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    Start,
+    Repeat,
+    Home,
+    OpenSettings,
+    OpenStats,
+    SetTime(u64),
+    SetTheme(String),
+    SetLanguage(String),
+    SetTopWords(usize),
+    SetLazy(bool),
+    SetTextStyle(TextStyle),
+    SetMode(Vec<String>),
+    SetCursor(String),
+    SetProfile(Option<String>),
+    ExportHistory,
+    Quit,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub label: String,
+    pub command: Command,
+}
+
+impl Entry {
+    fn new(label: impl Into<String>, command: Command) -> Self {
+        Entry {
+            label: label.into(),
+            command,
+        }
+    }
+}
+
+pub struct PaletteState {
+    pub query: String,
+    pub cursor: usize,
+    entries: Vec<Entry>,
+}
+
+impl PaletteState {
+    /// The palette with every command, including one per config profile.
+    /// Word counts are offered when the language's list ranks `ranked` words.
+    pub fn new(profiles: &[String], ranked: Option<usize>) -> Self {
+        PaletteState::with_entries(entries(profiles, ranked))
+    }
+
+    pub fn with_entries(entries: Vec<Entry>) -> Self {
+        PaletteState {
+            query: String::new(),
+            cursor: 0,
+            entries,
+        }
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.cursor = 0;
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.cursor = 0;
+    }
+
+    pub fn move_down(&mut self) {
+        let len = self.matches().len();
+        self.cursor = (self.cursor + 1).min(len.saturating_sub(1));
+    }
+
+    pub fn move_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    /// Entries matching the query, best first. A `set time <n>` query also
+    /// offers that exact duration even if it is not one of the presets.
+    pub fn matches(&self) -> Vec<Entry> {
+        let mut scored: Vec<(i64, &Entry)> = self
+            .entries
+            .iter()
+            .filter_map(|e| fuzzy_score(&self.query, &e.label).map(|s| (s, e)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+
+        let mut matches: Vec<Entry> = Vec::new();
+        if let Some(time) = custom_time(&self.query) {
+            matches.push(Entry::new(
+                format!("set time {time}"),
+                Command::SetTime(time),
+            ));
+        }
+        for (_, entry) in scored {
+            if !matches.iter().any(|m| m.command == entry.command) {
+                matches.push(entry.clone());
+            }
+        }
+        matches.truncate(MAX_MATCHES);
+        matches
+    }
+
+    pub fn selected(&self) -> Option<Command> {
+        self.matches()
+            .into_iter()
+            .nth(self.cursor)
+            .map(|e| e.command)
+    }
+}
+
+fn entries(profiles: &[String], ranked: Option<usize>) -> Vec<Entry> {
+    let mut entries = vec![
+        Entry::new("start test", Command::Start),
+        Entry::new("repeat test", Command::Repeat),
+        Entry::new("open stats", Command::OpenStats),
+        Entry::new("open settings", Command::OpenSettings),
+        Entry::new("go home", Command::Home),
+        Entry::new("export history", Command::ExportHistory),
+    ];
+    for time in TIME_PRESETS {
+        entries.push(Entry::new(
+            format!("set time {time}"),
+            Command::SetTime(*time),
+        ));
+    }
+    for (label, tokens) in mode_options() {
+        let tokens = tokens.iter().map(|t| t.to_string()).collect();
+        entries.push(Entry::new(
+            format!("mode {label}"),
+            Command::SetMode(tokens),
+        ));
+    }
+    for theme in available_themes() {
+        entries.push(Entry::new(
+            format!("theme {}", theme.to_lowercase()),
+            Command::SetTheme(theme),
+        ));
+    }
+    for language in available_languages() {
+        entries.push(Entry::new(
+            format!("language {language}"),
+            Command::SetLanguage(language),
+        ));
+    }
+    for top in top_words_options(ranked) {
+        entries.push(Entry::new(
+            format!("words {}", top_words_label(top)),
+            Command::SetTopWords(top),
+        ));
+    }
+    for style in TextStyle::ALL {
+        entries.push(Entry::new(
+            format!("text {}", style.name()),
+            Command::SetTextStyle(*style),
+        ));
+    }
+    for accents in ACCENT_OPTIONS {
+        entries.push(Entry::new(
+            format!("accents {accents}"),
+            Command::SetLazy(accents == "lazy"),
+        ));
+    }
+    for cursor in CURSOR_OPTIONS {
+        entries.push(Entry::new(
+            format!("cursor {cursor}"),
+            Command::SetCursor(cursor.to_string()),
+        ));
+    }
+    for profile in profiles {
+        entries.push(Entry::new(
+            format!("profile {profile}"),
+            Command::SetProfile(Some(profile.clone())),
+        ));
+    }
+    if !profiles.is_empty() {
+        entries.push(Entry::new(
+            format!("profile {NO_PROFILE}"),
+            Command::SetProfile(None),
+        ));
+    }
+    entries.push(Entry::new("quit", Command::Quit));
+    entries
+}
+
+fn custom_time(query: &str) -> Option<u64> {
+    let mut words = query.split_whitespace();
+    if words.next()? != "set" || words.next()? != "time" {
+        return None;
+    }
+    let time = words.next()?.parse::<Seconds>().ok()?;
+    words.next().is_none().then_some(time.get())
+}
+
+/// Scores `text` against `query` as a case-insensitive subsequence match,
+/// favouring consecutive characters and matches at the start of words.
+/// Whitespace in the query is ignored. Returns `None` if it doesn't match.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i64> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut pos = 0;
+    let mut last: Option<usize> = None;
+
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (pos..text.len()).find(|&i| text[i] == q)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || text[found - 1] == ' ' {
+            score += 3;
+        }
+        score -= (found - pos).min(3) as i64;
+        last = Some(found);
+        pos = found + 1;
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod palette_tests {
+    use super::*;
+
+    fn sample() -> PaletteState {
+        PaletteState::with_entries(vec![
+            Entry::new("open stats", Command::OpenStats),
+            Entry::new("set time 30", Command::SetTime(30)),
+            Entry::new("set time 60", Command::SetTime(60)),
+            Entry::new("theme nord", Command::SetTheme("Nord".into())),
+            Entry::new("language german", Command::SetLanguage("german".into())),
+            Entry::new("export history", Command::ExportHistory),
+        ])
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("", "anything").is_some());
+        assert!(fuzzy_score("tn", "theme nord").is_some());
+        assert!(fuzzy_score("nt", "theme").is_none());
+        assert!(fuzzy_score("STATS", "open stats").is_some());
+        assert!(fuzzy_score("stats", "open stats") > fuzzy_score("sts", "open stats"));
+    }
+
+    #[test]
+    fn test_best_match_first() {
+        let mut palette = sample();
+        for c in "lang ger".chars() {
+            palette.push(c);
+        }
+        assert_eq!(
+            palette.selected(),
+            Some(Command::SetLanguage("german".into()))
+        );
+    }
+
+    #[test]
+    fn test_custom_time() {
+        let mut palette = sample();
+        for c in "set time 45".chars() {
+            palette.push(c);
+        }
+        assert_eq!(palette.selected(), Some(Command::SetTime(45)));
+
+        let mut palette = sample();
+        for c in "set time 60".chars() {
+            palette.push(c);
+        }
+        let matches = palette.matches();
+        assert_eq!(matches[0].command, Command::SetTime(60));
+        assert_eq!(
+            matches
+                .iter()
+                .filter(|m| m.command == Command::SetTime(60))
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_cursor_stays_in_bounds() {
+        let mut palette = sample();
+        for c in "export".chars() {
+            palette.push(c);
+        }
+        palette.move_down();
+        palette.move_down();
+        assert_eq!(palette.selected(), Some(Command::ExportHistory));
+        palette.move_up();
+        assert_eq!(palette.cursor, 0);
+    }
+}
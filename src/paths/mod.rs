@@ -0,0 +1,240 @@
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use dirs::home_dir;
+
+/// Where typy keeps its files: the config file, the directory holding
+/// `theme.toml` and the data directory holding scores, word lists and
+/// corpora.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Paths {
+    /// The one file typy reads its config from and writes it to.
+    config_file: Option<PathBuf>,
+    config_dir: Option<PathBuf>,
+    data_dir: Option<PathBuf>,
+}
+
+impl Paths {
+    /// Resolves all locations. The flag wins over the `TYPY_*` variable,
+    /// which wins over a `config.toml` in the working directory `cwd` (for
+    /// the config file only), the XDG base directories and finally the home
+    /// directory.
+    fn resolve(
+        config: Option<PathBuf>,
+        data_dir: Option<PathBuf>,
+        var: impl Fn(&str) -> Option<OsString>,
+        home: Option<&Path>,
+        cwd: Option<&Path>,
+    ) -> Self {
+        let explicit = config.or_else(|| env_path(&var, "TYPY_CONFIG"));
+        let local = cwd
+            .map(|d| d.join("config.toml"))
+            .filter(|f| explicit.is_none() && f.is_file());
+        let default = explicit.or_else(|| {
+            base_dir(&var, "XDG_CONFIG_HOME", home, ".config").map(|d| d.join("typy/config.toml"))
+        });
+        let data_dir = data_dir
+            .or_else(|| env_path(&var, "TYPY_DATA_DIR"))
+            .or_else(|| {
+                base_dir(&var, "XDG_DATA_HOME", home, ".local/share").map(|d| d.join("typy"))
+            });
+        Paths {
+            // themes stay with the default file when a local one is used
+            config_dir: default
+                .as_deref()
+                .and_then(Path::parent)
+                .map(Path::to_path_buf),
+            config_file: local.or(default),
+            data_dir,
+        }
+    }
+}
+
+fn env_path(var: &impl Fn(&str) -> Option<OsString>, name: &str) -> Option<PathBuf> {
+    var(name).filter(|v| !v.is_empty()).map(PathBuf::from)
+}
+
+/// An XDG base directory, ignoring relative values as the spec requires.
+fn base_dir(
+    var: &impl Fn(&str) -> Option<OsString>,
+    name: &str,
+    home: Option<&Path>,
+    fallback: &str,
+) -> Option<PathBuf> {
+    env_path(var, name)
+        .filter(|p| p.is_absolute())
+        .or_else(|| home.map(|h| h.join(fallback)))
+}
+
+static PATHS: OnceLock<Paths> = OnceLock::new();
+
+fn paths() -> &'static Paths {
+    PATHS.get_or_init(|| {
+        Paths::resolve(
+            None,
+            None,
+            |k| env::var_os(k),
+            home_dir().as_deref(),
+            env::current_dir().ok().as_deref(),
+        )
+    })
+}
+
+/// Applies the `--config` and `--data-dir` flags. Must run before anything
+/// reads a path; later calls are ignored.
+pub fn init(config: Option<PathBuf>, data_dir: Option<PathBuf>) {
+    let _ = PATHS.set(Paths::resolve(
+        config,
+        data_dir,
+        |k| env::var_os(k),
+        home_dir().as_deref(),
+        env::current_dir().ok().as_deref(),
+    ));
+}
+
+/// Points every location into an empty scratch directory, so tests never
+/// read the user's config or word lists.
+#[cfg(test)]
+pub(crate) fn init_for_tests() {
+    let dir = env::temp_dir().join(format!("typy-tests-{}", std::process::id()));
+    init(Some(dir.join("config.toml")), Some(dir.join("data")));
+}
+
+/// The config file typy reads and writes: `./config.toml` if there is one
+/// and no file was given with `--config` or `TYPY_CONFIG`, otherwise
+/// `~/.config/typy/config.toml` by default. It need not exist yet.
+pub fn config_file() -> Option<PathBuf> {
+    paths().config_file.clone()
+}
+
+/// The directory where `theme.toml` lives: that of the default or given
+/// config file.
+pub fn config_dir() -> Option<PathBuf> {
+    paths().config_dir.clone()
+}
+
+/// The data directory, `~/.local/share/typy` by default.
+pub fn data_dir() -> Option<PathBuf> {
+    paths().data_dir.clone()
+}
+
+#[cfg(test)]
+mod paths_tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn resolve(
+        config: Option<&str>,
+        data_dir: Option<&str>,
+        vars: &[(&str, &str)],
+        home: Option<&str>,
+    ) -> Paths {
+        resolve_in(config, data_dir, vars, home, None)
+    }
+
+    fn resolve_in(
+        config: Option<&str>,
+        data_dir: Option<&str>,
+        vars: &[(&str, &str)],
+        home: Option<&str>,
+        cwd: Option<&Path>,
+    ) -> Paths {
+        let vars: HashMap<String, OsString> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), OsString::from(v)))
+            .collect();
+        Paths::resolve(
+            config.map(PathBuf::from),
+            data_dir.map(PathBuf::from),
+            |k| vars.get(k).cloned(),
+            home.map(Path::new),
+            cwd,
+        )
+    }
+
+    #[test]
+    fn test_home_defaults() {
+        let paths = resolve(None, None, &[], Some("/home/ada"));
+        assert_eq!(
+            paths.config_file,
+            Some(PathBuf::from("/home/ada/.config/typy/config.toml"))
+        );
+        assert_eq!(
+            paths.data_dir,
+            Some(PathBuf::from("/home/ada/.local/share/typy"))
+        );
+        assert_eq!(
+            paths.config_dir,
+            Some(PathBuf::from("/home/ada/.config/typy"))
+        );
+        assert_eq!(resolve(None, None, &[], None), Paths::default());
+    }
+
+    #[test]
+    fn test_xdg_base_directories() {
+        let vars = [("XDG_CONFIG_HOME", "/cfg"), ("XDG_DATA_HOME", "/data")];
+        let paths = resolve(None, None, &vars, Some("/home/ada"));
+        assert_eq!(
+            paths.config_file,
+            Some(PathBuf::from("/cfg/typy/config.toml"))
+        );
+        assert_eq!(paths.data_dir, Some(PathBuf::from("/data/typy")));
+
+        let relative = [("XDG_CONFIG_HOME", "cfg"), ("XDG_DATA_HOME", "")];
+        let paths = resolve(None, None, &relative, Some("/home/ada"));
+        assert_eq!(
+            paths.config_file,
+            Some(PathBuf::from("/home/ada/.config/typy/config.toml"))
+        );
+        assert_eq!(
+            paths.data_dir,
+            Some(PathBuf::from("/home/ada/.local/share/typy"))
+        );
+    }
+
+    #[test]
+    fn test_env_and_flags_take_precedence() {
+        let vars = [
+            ("XDG_CONFIG_HOME", "/cfg"),
+            ("TYPY_CONFIG", "/profile/typy.toml"),
+            ("TYPY_DATA_DIR", "/profile/data"),
+        ];
+        let paths = resolve(None, None, &vars, None);
+        assert_eq!(paths.config_file, Some(PathBuf::from("/profile/typy.toml")));
+        assert_eq!(paths.data_dir, Some(PathBuf::from("/profile/data")));
+
+        let paths = resolve(Some("a.toml"), Some("scratch"), &vars, None);
+        assert_eq!(paths.config_file, Some(PathBuf::from("a.toml")));
+        assert_eq!(paths.data_dir, Some(PathBuf::from("scratch")));
+    }
+
+    #[test]
+    fn test_working_directory_config() {
+        let cwd = env::temp_dir().join(format!("typy-paths-{}", std::process::id()));
+        std::fs::create_dir_all(&cwd).unwrap();
+        let local = cwd.join("config.toml");
+        let _ = std::fs::remove_file(&local);
+
+        // only an existing file counts
+        let paths = resolve_in(None, None, &[], Some("/home/ada"), Some(&cwd));
+        assert_eq!(
+            paths.config_file,
+            Some(PathBuf::from("/home/ada/.config/typy/config.toml"))
+        );
+
+        std::fs::write(&local, "").unwrap();
+        let paths = resolve_in(None, None, &[], Some("/home/ada"), Some(&cwd));
+        assert_eq!(paths.config_file, Some(local.clone()));
+        assert_eq!(
+            paths.config_dir,
+            Some(PathBuf::from("/home/ada/.config/typy"))
+        );
+
+        let vars = [("TYPY_CONFIG", "/profile/typy.toml")];
+        let paths = resolve_in(None, None, &vars, None, Some(&cwd));
+        assert_eq!(paths.config_file, Some(PathBuf::from("/profile/typy.toml")));
+        std::fs::remove_dir_all(&cwd).unwrap();
+    }
+}
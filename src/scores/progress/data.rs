@@ -4,29 +4,33 @@ use serde::{Deserialize, Serialize};
 use serde_json::to_writer_pretty;
 use std::collections::BTreeMap;
 use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::paths;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Averages {
     pub wpm_avg: WpmAvg,
     pub raw_avg: RawAvg,
     pub accuracy_avg: AccuracyAvg,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WpmAvg {
     pub avg: f32,
     count: u32,
     sum_all: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawAvg {
     pub avg: f32,
     count: u32,
     sum_all: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccuracyAvg {
     pub avg: f32,
     count: u32,
@@ -39,6 +43,12 @@ pub struct Score {
     pub wpm: u32,
     pub raw: u32,
     pub accuracy: f32,
+    /// Accuracy on digits, for tests that contained any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digit_accuracy: Option<f32>,
+    /// The config profile the test was played with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -81,9 +91,13 @@ impl Data {
         Ok(Data::get_records()?.get(&duration).copied().unwrap_or(0))
     }
 
+    fn data_dir() -> Result<PathBuf> {
+        paths::data_dir()
+            .context("Failed to find the data directory; set XDG_DATA_HOME or TYPY_DATA_DIR")
+    }
+
     pub fn get_data() -> Result<Data> {
-        let mut path = dirs::home_dir().context("Failed to get home directory")?;
-        path.push(".local/share/typy/scores.json");
+        let path = Data::data_dir()?.join("scores.json");
 
         if !path.exists() {
             if let Some(parent) = path.parent() {
@@ -102,8 +116,7 @@ impl Data {
     }
 
     fn write_to_file(data: Data) -> Result<()> {
-        let mut path = dirs::home_dir().context("Failed to get home directory")?;
-        path.push(".local/share/typy/scores.json");
+        let path = Data::data_dir()?.join("scores.json");
 
         if !path.exists() {
             return Err(anyhow::anyhow!("File does not exist"));
@@ -124,6 +137,47 @@ impl Data {
         let data = Data::get_data()?;
         Ok(data.scores)
     }
+
+    /// Writes the saved scores as CSV next to `scores.json` and returns the path.
+    pub fn export_history() -> Result<PathBuf> {
+        let mut scores = Data::get_scores()?;
+        Score::sort_scores(&mut scores);
+
+        let mut path = Data::data_dir()?;
+        path.push(format!(
+            "history-{}.csv",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        ));
+
+        let mut file = File::create(&path).context("Failed to create history file")?;
+        writeln!(file, "date,time,wpm,raw,accuracy,digit_accuracy,profile")?;
+        for score in &scores {
+            writeln!(
+                file,
+                "{},{},{},{},{:.2},{},{}",
+                csv_field(&score.get_date()),
+                csv_field(&score.get_time()),
+                score.wpm,
+                score.raw,
+                score.accuracy,
+                score
+                    .digit_accuracy
+                    .map(|a| format!("{a:.2}"))
+                    .unwrap_or_default(),
+                csv_field(score.profile.as_deref().unwrap_or_default())
+            )?;
+        }
+        Ok(path)
+    }
+}
+
+/// `field` as one CSV field, quoted when it holds a comma, quote or line break.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
 }
 
 impl Default for Data {
@@ -162,9 +216,21 @@ impl Score {
             wpm,
             raw,
             accuracy,
+            digit_accuracy: None,
+            profile: None,
         }
     }
 
+    pub fn with_digit_accuracy(mut self, digit_accuracy: Option<f64>) -> Score {
+        self.digit_accuracy = digit_accuracy.map(|a| a as f32);
+        self
+    }
+
+    pub fn with_profile(mut self, profile: Option<String>) -> Score {
+        self.profile = profile;
+        self
+    }
+
     pub fn get_date(&self) -> String {
         self.timestamp.format("%Y-%m-%d").to_string()
     }
@@ -174,7 +240,7 @@ impl Score {
     }
 
     pub fn sort_scores(scores: &mut [Score]) {
-        scores.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        scores.sort_by_key(|s| std::cmp::Reverse(s.timestamp));
     }
 
     fn update_scores(score: &Score) -> Result<Vec<Score>> {
@@ -198,6 +264,24 @@ impl Averages {
     fn new(score: Score) -> Result<Self> {
         Self::calculate_averages(score)
     }
+
+    /// The averages of just `scores`, e.g. those of one profile.
+    pub fn of<'a>(scores: impl IntoIterator<Item = &'a Score>) -> Averages {
+        let mut averages = Data::default().averages;
+        for score in scores {
+            averages.wpm_avg.sum_all += score.wpm;
+            averages.raw_avg.sum_all += score.raw;
+            averages.accuracy_avg.sum_all += score.accuracy;
+            averages.wpm_avg.count += 1;
+        }
+        let count = averages.wpm_avg.count.max(1) as f32;
+        averages.raw_avg.count = averages.wpm_avg.count;
+        averages.accuracy_avg.count = averages.wpm_avg.count;
+        averages.wpm_avg.avg = averages.wpm_avg.sum_all as f32 / count;
+        averages.raw_avg.avg = averages.raw_avg.sum_all as f32 / count;
+        averages.accuracy_avg.avg = averages.accuracy_avg.sum_all / count;
+        averages
+    }
     fn calculate_averages(score: Score) -> Result<Averages> {
         let averages = Data::get_averages()?;
         let mut wpm_sum = averages.wpm_avg.sum_all;
@@ -241,3 +325,15 @@ impl Averages {
         })
     }
 }
+
+#[cfg(test)]
+mod data_tests {
+    use super::*;
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("code"), "code");
+        assert_eq!(csv_field("code, fast"), "\"code, fast\"");
+        assert_eq!(csv_field("the \"good\" one"), "\"the \"\"good\"\" one\"");
+    }
+}
@@ -8,6 +8,8 @@ pub struct Stats {
     extra: i32,
     missed: i32,
     spaces: i32,
+    digits_correct: i32,
+    digits_total: i32,
     elapsed_secs: f64,
 }
 
@@ -21,6 +23,8 @@ impl Stats {
             extra: 0,
             missed: 0,
             spaces: 0,
+            digits_correct: 0,
+            digits_total: 0,
             elapsed_secs: 0.0,
         }
     }
@@ -47,6 +51,16 @@ impl Stats {
         self.elapsed_secs = elapsed_secs;
     }
 
+    /// Records how many of the digits in the text were typed correctly.
+    pub fn record_digits(&mut self, correct: i32, total: i32) {
+        self.digits_correct = correct;
+        self.digits_total = total;
+    }
+
+    pub fn elapsed_secs(&self) -> f64 {
+        self.elapsed_secs
+    }
+
     fn minutes(&self) -> f64 {
         self.elapsed_secs / 60.0
     }
@@ -78,6 +92,18 @@ impl Stats {
             self.correct as f64 / total as f64 * 100.0
         }
     }
+
+    /// Accuracy on digits alone, or `None` if the text had no digits.
+    pub fn digit_accuracy(&self) -> Option<f64> {
+        (self.digits_total > 0)
+            .then(|| self.digits_correct as f64 / self.digits_total as f64 * 100.0)
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Stats::new()
+    }
 }
 
 #[cfg(test)]
@@ -136,4 +162,12 @@ mod stats_tests {
         assert_eq!(zero_time.wpm(), 0.0);
         assert_eq!(zero_time.accuracy(), 100.0);
     }
+
+    #[test]
+    fn digit_accuracy_only_with_digits() {
+        let mut s = finalized(10, 0, 0, 0, 2, 30.0);
+        assert_eq!(s.digit_accuracy(), None);
+        s.record_digits(3, 4);
+        assert_eq!(s.digit_accuracy(), Some(75.0));
+    }
 }
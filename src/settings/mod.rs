@@ -1,11 +1,17 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
+use crate::config::model::{Seconds, TIME_PRESETS};
+use crate::config::schema::{KeySpec, Kind, KEYS};
 use crate::theme::available_themes;
 use crate::word_provider::available_languages;
 
-const TIME_OPTIONS: &[u64] = &[15, 30, 60, 120];
+/// The last time option, which asks for a duration instead.
+pub const CUSTOM_TIME: &str = "custom…";
 
-const CURSOR_OPTIONS: &[&str] = &[
+/// The profile option for the base settings alone.
+pub const NO_PROFILE: &str = "none";
+
+pub const CURSOR_OPTIONS: &[&str] = &[
     "block",
     "blinking block",
     "underline",
@@ -14,103 +20,192 @@ const CURSOR_OPTIONS: &[&str] = &[
     "blinking bar",
 ];
 
-fn mode_options() -> Vec<(&'static str, Vec<&'static str>)> {
+/// Word list sizes offered for ranked lists; `0` keeps every word.
+pub const TOP_WORDS_OPTIONS: &[usize] = &[0, 100, 200, 1000, 5000, 10000];
+
+/// The sizes that shorten a list ranking `ranked` words; none for a list
+/// without a ranking.
+pub fn top_words_options(ranked: Option<usize>) -> Vec<usize> {
+    let Some(len) = ranked else {
+        return Vec::new();
+    };
+    TOP_WORDS_OPTIONS
+        .iter()
+        .copied()
+        .filter(|&n| n < len)
+        .collect()
+}
+
+pub fn top_words_label(top_words: usize) -> String {
+    match top_words {
+        0 => "all".to_string(),
+        n if n % 1000 == 0 => format!("top {}k", n / 1000),
+        n => format!("top {n}"),
+    }
+}
+
+/// How accented letters are checked: exactly, or also without the accent.
+pub const ACCENT_OPTIONS: [&str; 2] = ["exact", "lazy"];
+
+pub fn mode_options() -> Vec<(&'static str, Vec<&'static str>)> {
     vec![
         ("normal", vec!["normal"]),
         ("uppercase", vec!["uppercase"]),
         ("punctuation", vec!["punctuation"]),
+        ("numbers", vec!["numbers"]),
         ("uppercase + punctuation", vec!["uppercase", "punctuation"]),
     ]
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum Field {
-    Theme,
-    Cursor,
-    Language,
-    Mode,
-    Time,
+/// The preferences shown on the settings page and saved to the config file.
+pub struct Preferences<'a> {
+    /// The value of each key with a settings row, by dotted key, as `typy
+    /// config get` shows it, e.g. `30` for `game.time`.
+    pub values: BTreeMap<String, String>,
+    /// How many words the language's list ranks; `None` if it has no
+    /// ranking, which hides the words row.
+    pub ranked: Option<usize>,
+    /// The active profile; settings are saved to its table.
+    pub profile: Option<&'a str>,
+    /// Every profile in the config file, offered in the profile row.
+    pub profiles: &'a [String],
 }
 
+/// One row of the settings page, for a key of [`KEYS`] that has one.
 pub struct Row {
     pub label: &'static str,
-    pub field: Field,
+    pub key: &'static KeySpec,
+    /// What the dropdown shows.
     pub options: Vec<String>,
+    /// The config value of each option.
+    values: Vec<String>,
     pub selected: usize,
 }
 
+impl Row {
+    /// The row for `key`; `None` if it offers no choice, like the words row
+    /// for a list without a ranking.
+    fn new(key: &'static KeySpec, preferences: &Preferences) -> Option<Self> {
+        let label = key.row?.label;
+        let current = match key.kind {
+            Kind::Profile => preferences.profile.unwrap_or_default().to_string(),
+            _ => preferences.values.get(&key.path())?.clone(),
+        };
+        let mut choices = choices(key, preferences);
+        let position = |choices: &[(String, String)]| {
+            choices
+                .iter()
+                .position(|(_, value)| same_value(key.kind, value, &current))
+        };
+        // a time or word count set in the file is listed with the presets,
+        // unless the list is too short for that many words
+        let fits = |n: usize| preferences.ranked.is_some_and(|len| n < len);
+        let listed = match key.kind {
+            Kind::Time => true,
+            Kind::TopWords => current.parse().is_ok_and(fits),
+            _ => false,
+        };
+        if position(&choices).is_none() && listed {
+            let label = match key.kind {
+                Kind::TopWords => top_words_label(current.parse().unwrap_or(0)),
+                _ => current.clone(),
+            };
+            choices.push((label, current.clone()));
+        }
+        let selected = position(&choices).unwrap_or(0);
+        if key.kind == Kind::Time {
+            choices.push((CUSTOM_TIME.to_string(), CUSTOM_TIME.to_string()));
+        }
+        if choices.len() < 2 {
+            return None;
+        }
+        let (options, values) = choices.into_iter().unzip();
+        Some(Row {
+            label,
+            key,
+            options,
+            values,
+            selected,
+        })
+    }
+
+    fn value(&self) -> &str {
+        &self.values[self.selected]
+    }
+}
+
+/// What the row of `key` offers: the label and config value of each option.
+fn choices(key: &KeySpec, preferences: &Preferences) -> Vec<(String, String)> {
+    let same = |values: Vec<String>| values.into_iter().map(|v| (v.clone(), v)).collect();
+    match key.kind {
+        Kind::Profile if preferences.profiles.is_empty() => Vec::new(),
+        Kind::Profile => {
+            let mut profiles = vec![(NO_PROFILE.to_string(), String::new())];
+            profiles.extend(preferences.profiles.iter().map(|p| (p.clone(), p.clone())));
+            profiles
+        }
+        Kind::Theme => same(available_themes()),
+        Kind::Language => same(available_languages()),
+        Kind::OneOf(options) => same(options.iter().map(|o| o.to_string()).collect()),
+        Kind::Bool => {
+            let names = key.row.and_then(|r| r.names).unwrap_or(["false", "true"]);
+            vec![
+                (names[0].to_string(), "false".to_string()),
+                (names[1].to_string(), "true".to_string()),
+            ]
+        }
+        Kind::Modes => mode_options()
+            .into_iter()
+            .map(|(label, tokens)| (label.to_string(), tokens.join(", ")))
+            .collect(),
+        Kind::Time => same(TIME_PRESETS.iter().map(|t| t.to_string()).collect()),
+        Kind::TopWords => top_words_options(preferences.ranked)
+            .into_iter()
+            .map(|n| (top_words_label(n), n.to_string()))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Whether two values of `kind` mean the same; modes are a set.
+fn same_value(kind: Kind, a: &str, b: &str) -> bool {
+    match kind {
+        Kind::Modes => {
+            let modes = |m: &str| -> BTreeSet<String> {
+                m.split(',').map(|m| m.trim().to_string()).collect()
+            };
+            modes(a) == modes(b)
+        }
+        _ => a == b,
+    }
+}
+
 pub struct SettingsState {
     pub rows: Vec<Row>,
     pub cursor: usize,
     pub open: bool,
     pub dropdown_cursor: usize,
+    /// The custom duration being typed, once `custom…` is chosen.
+    pub input: Option<String>,
+    /// Why the typed duration was rejected.
+    pub input_error: Option<String>,
+    /// The values the page opened with, kept for keys without a row.
+    values: BTreeMap<String, String>,
 }
 
 impl SettingsState {
-    pub fn new(
-        theme: &str,
-        cursor: &str,
-        language: &str,
-        mode_tokens: &[String],
-        time: u64,
-    ) -> Self {
-        let themes = available_themes();
-        let theme_sel = themes.iter().position(|t| t == theme).unwrap_or(0);
-
-        let cursor_options: Vec<String> = CURSOR_OPTIONS.iter().map(|c| c.to_string()).collect();
-        let cursor_sel = cursor_options.iter().position(|c| c == cursor).unwrap_or(0);
-
-        let languages = available_languages();
-        let language_sel = languages.iter().position(|l| l == language).unwrap_or(0);
-
-        let modes = mode_options();
-        let mode_labels: Vec<String> = modes.iter().map(|(l, _)| l.to_string()).collect();
-        let mode_sel = modes
-            .iter()
-            .position(|(_, toks)| tokens_match(toks, mode_tokens))
-            .unwrap_or(0);
-
-        let time_labels: Vec<String> = TIME_OPTIONS.iter().map(|t| t.to_string()).collect();
-        let time_sel = TIME_OPTIONS.iter().position(|&t| t == time).unwrap_or(1);
-
-        let rows = vec![
-            Row {
-                label: "theme",
-                field: Field::Theme,
-                options: themes,
-                selected: theme_sel,
-            },
-            Row {
-                label: "cursor",
-                field: Field::Cursor,
-                options: cursor_options,
-                selected: cursor_sel,
-            },
-            Row {
-                label: "language",
-                field: Field::Language,
-                options: languages,
-                selected: language_sel,
-            },
-            Row {
-                label: "mode",
-                field: Field::Mode,
-                options: mode_labels,
-                selected: mode_sel,
-            },
-            Row {
-                label: "time",
-                field: Field::Time,
-                options: time_labels,
-                selected: time_sel,
-            },
-        ];
-
+    pub fn new(preferences: &Preferences) -> Self {
         SettingsState {
-            rows,
+            rows: KEYS
+                .iter()
+                .filter_map(|key| Row::new(key, preferences))
+                .collect(),
             cursor: 0,
             open: false,
             dropdown_cursor: 0,
+            input: None,
+            input_error: None,
+            values: preferences.values.clone(),
         }
     }
 
@@ -140,52 +235,258 @@ impl SettingsState {
         self.open = false;
     }
 
-    pub fn confirm(&mut self) {
+    /// Picks the highlighted option; `false` if it was `custom…`, which
+    /// starts typing a duration instead.
+    pub fn confirm(&mut self) -> bool {
+        if self.rows[self.cursor].options[self.dropdown_cursor] == CUSTOM_TIME {
+            self.input = Some(String::new());
+            return false;
+        }
         self.rows[self.cursor].selected = self.dropdown_cursor;
         self.open = false;
+        true
     }
 
-    fn option_of(&self, field: Field) -> &str {
-        let row = self
-            .rows
-            .iter()
-            .find(|r| r.field == field)
-            .expect("settings row missing");
-        &row.options[row.selected]
+    /// Types into the custom duration, which takes digits and an `s` or `m`.
+    pub fn push_input(&mut self, c: char) {
+        if let Some(input) = self.input.as_mut() {
+            if input.len() < 8 && (c.is_ascii_digit() || c == 's' || c == 'm') {
+                input.push(c);
+                self.input_error = None;
+            }
+        }
     }
 
-    pub fn theme_name(&self) -> String {
-        self.option_of(Field::Theme).to_string()
+    pub fn pop_input(&mut self) {
+        if let Some(input) = self.input.as_mut() {
+            input.pop();
+            self.input_error = None;
+        }
     }
 
-    pub fn cursor_style(&self) -> String {
-        self.option_of(Field::Cursor).to_string()
+    pub fn cancel_input(&mut self) {
+        self.input = None;
+        self.input_error = None;
     }
 
-    pub fn language(&self) -> String {
-        self.option_of(Field::Language).to_string()
+    /// Selects the typed duration, listing it next to the presets; `false`
+    /// if it is not a valid duration.
+    pub fn submit_input(&mut self) -> bool {
+        let Some(input) = self.input.as_ref() else {
+            return false;
+        };
+        let time = match input.parse::<Seconds>() {
+            Ok(time) => time.to_string(),
+            Err(e) => {
+                self.input_error = Some(e.to_string());
+                return false;
+            }
+        };
+        let row = &mut self.rows[self.cursor];
+        let selected = match row.values.iter().position(|v| *v == time) {
+            Some(i) => i,
+            None => {
+                let custom = TIME_PRESETS.len();
+                if row.options.len() > custom + 1 {
+                    row.options[custom] = time.clone();
+                    row.values[custom] = time;
+                } else {
+                    row.options.insert(custom, time.clone());
+                    row.values.insert(custom, time);
+                }
+                custom
+            }
+        };
+        row.selected = selected;
+        self.cancel_input();
+        self.open = false;
+        true
     }
 
-    pub fn time(&self) -> u64 {
-        self.option_of(Field::Time).parse().unwrap_or(30)
+    /// The chosen value of the dotted `key`, e.g. `30` for `game.time`.
+    pub fn value(&self, key: &str) -> Option<&str> {
+        match self.rows.iter().find(|r| r.key.path() == key) {
+            Some(row) => Some(row.value()),
+            None => self.values.get(key).map(String::as_str),
+        }
     }
 
-    pub fn mode_tokens(&self) -> Vec<String> {
-        let label = self.option_of(Field::Mode);
-        mode_options()
-            .into_iter()
-            .find(|(l, _)| *l == label)
-            .map(|(_, toks)| toks.iter().map(|s| s.to_string()).collect())
-            .unwrap_or_else(|| vec!["normal".to_string()])
+    /// The chosen profile; `None` for the base settings or when the config
+    /// file has no profiles.
+    pub fn profile(&self) -> Option<String> {
+        let row = self.rows.iter().find(|r| r.key.kind == Kind::Profile)?;
+        Some(row.value().to_string()).filter(|p| !p.is_empty())
     }
+}
 
-    pub fn mode_default_string(&self) -> String {
-        self.mode_tokens().join(", ")
+#[cfg(test)]
+mod settings_tests {
+    use super::*;
+
+    fn values(top_words: usize) -> BTreeMap<String, String> {
+        [
+            ("theme", ""),
+            ("cursor", "block"),
+            ("language.lang", "english"),
+            ("language.text_style", "sentences"),
+            ("language.top_words", &top_words.to_string()),
+            ("language.lazy", "false"),
+            ("modes.default_mode", "normal"),
+            ("game.time", "30"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+    }
+
+    fn state(top_words: usize) -> SettingsState {
+        crate::paths::init_for_tests();
+        SettingsState::new(&Preferences {
+            values: values(top_words),
+            ranked: Some(50_000),
+            profile: None,
+            profiles: &[],
+        })
+    }
+
+    fn row<'a>(settings: &'a mut SettingsState, key: &str) -> &'a mut Row {
+        let mut rows = settings.rows.iter_mut();
+        rows.find(|r| r.key.path() == key).unwrap()
+    }
+
+    #[test]
+    fn test_top_words_labels() {
+        assert_eq!(top_words_label(0), "all");
+        assert_eq!(top_words_label(1000), "top 1k");
+        assert_eq!(top_words_label(250), "top 250");
+    }
+
+    #[test]
+    fn test_top_words_row_roundtrip() {
+        for top_words in [0, 200, 5000, 250, 3000] {
+            let top = top_words.to_string();
+            assert_eq!(state(top_words).value("language.top_words"), Some(&*top));
+        }
+        let mut words = state(250);
+        let row = row(&mut words, "language.top_words");
+        assert_eq!(row.options.len(), TOP_WORDS_OPTIONS.len() + 1);
+    }
+
+    #[test]
+    fn test_words_row_needs_a_ranked_list() {
+        assert_eq!(top_words_options(None), Vec::<usize>::new());
+        assert_eq!(top_words_options(Some(200)), [0, 100]);
+
+        crate::paths::init_for_tests();
+        let preferences = |ranked| Preferences {
+            values: values(1000),
+            ranked,
+            profile: None,
+            profiles: &[],
+        };
+        // a list without a ranking hides the row and keeps the saved value
+        let unranked = SettingsState::new(&preferences(None));
+        assert!(unranked.rows.iter().all(|r| r.key.kind != Kind::TopWords));
+        assert_eq!(unranked.value("language.top_words"), Some("1000"));
+
+        // a 200 word list offers no more than it has
+        let mut ranked = SettingsState::new(&preferences(Some(200)));
+        let row = row(&mut ranked, "language.top_words");
+        assert_eq!(row.options, ["all", "top 100"]);
+        assert_eq!(ranked.value("language.top_words"), Some("0"));
+    }
+
+    #[test]
+    fn test_text_row() {
+        let mut text = state(0);
+        assert_eq!(text.value("language.text_style"), Some("sentences"));
+        row(&mut text, "language.text_style").selected = 0;
+        assert_eq!(text.value("language.text_style"), Some("words"));
+    }
+
+    #[test]
+    fn test_accents_row() {
+        let mut lazy = state(0);
+        let row = row(&mut lazy, "language.lazy");
+        assert_eq!(row.options, ACCENT_OPTIONS);
+        row.selected = 1;
+        assert_eq!(lazy.value("language.lazy"), Some("true"));
     }
-}
 
-fn tokens_match(preset: &[&str], active: &[String]) -> bool {
-    let a: BTreeSet<String> = preset.iter().map(|s| s.to_string()).collect();
-    let b: BTreeSet<String> = active.iter().cloned().collect();
-    a == b
+    #[test]
+    fn test_mode_row_ignores_order() {
+        let mut preferences = Preferences {
+            values: values(0),
+            ranked: None,
+            profile: None,
+            profiles: &[],
+        };
+        let mode = preferences.values.get_mut("modes.default_mode").unwrap();
+        *mode = "punctuation,uppercase".to_string();
+        let mut settings = SettingsState::new(&preferences);
+        let row = row(&mut settings, "modes.default_mode");
+        assert_eq!(row.options[row.selected], "uppercase + punctuation");
+        let mode = settings.value("modes.default_mode");
+        assert_eq!(mode, Some("uppercase, punctuation"));
+    }
+
+    #[test]
+    fn test_profile_row() {
+        assert_eq!(state(0).rows[0].label, "theme");
+        assert_eq!(state(0).profile(), None);
+
+        let profiles = ["code".to_string(), "warmup".to_string()];
+        let mut settings = SettingsState::new(&Preferences {
+            values: values(0),
+            ranked: None,
+            profile: Some("warmup"),
+            profiles: &profiles,
+        });
+        assert_eq!(settings.rows[0].options, [NO_PROFILE, "code", "warmup"]);
+        assert_eq!(settings.profile().as_deref(), Some("warmup"));
+        settings.open();
+        settings.dropdown_cursor = 0;
+        settings.confirm();
+        assert_eq!(settings.profile(), None);
+    }
+
+    #[test]
+    fn test_custom_time() {
+        let mut settings = state(0);
+        settings.cursor = settings
+            .rows
+            .iter()
+            .position(|r| r.key.kind == Kind::Time)
+            .unwrap();
+        settings.open();
+        let custom = settings.rows[settings.cursor].options.len() - 1;
+        settings.dropdown_cursor = custom;
+        assert!(!settings.confirm());
+
+        for c in "9x0".chars() {
+            settings.push_input(c);
+        }
+        assert_eq!(settings.input.as_deref(), Some("90"));
+        settings.pop_input();
+        settings.push_input('0');
+        settings.push_input('0');
+        settings.push_input('0');
+        assert!(!settings.submit_input());
+        let error = settings.input_error.clone().unwrap();
+        assert!(error.contains("not 9000"), "{error}");
+
+        settings.input = Some("2m".to_string());
+        assert!(settings.submit_input());
+        assert_eq!(settings.value("game.time"), Some("120"));
+        assert!(!settings.open);
+
+        settings.open();
+        settings.dropdown_cursor = custom;
+        settings.confirm();
+        settings.input = Some("45".to_string());
+        assert!(settings.submit_input());
+        assert_eq!(settings.value("game.time"), Some("45"));
+        let row = &settings.rows[settings.cursor];
+        assert_eq!(row.options, ["15", "30", "60", "120", "45", CUSTOM_TIME]);
+    }
 }
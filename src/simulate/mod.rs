@@ -0,0 +1,441 @@
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+use serde::Serialize;
+
+use crate::app::{App, AppConfig};
+use crate::clock::FakeClock;
+use crate::config::Settings;
+use crate::keymap::KeyChord;
+use crate::ui;
+use crate::word_provider::{TextStyle, WordFilter};
+
+/// The app loop polls every 100ms, so waits are replayed in the same steps.
+const TICK: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Step {
+    Type(String),
+    Key(KeyChord),
+    Wait(Duration),
+    FocusLost,
+    FocusGained,
+}
+
+/// Parses a script with one step per line:
+///
+/// ```text
+/// # comments and blank lines are ignored
+/// key enter          # any chord from [keybindings], e.g. `key ctrl+p`
+/// type hello world   # types the text, spaces included
+/// wait 1.5           # advances the clock by seconds
+/// focus-lost
+/// focus-gained
+/// ```
+pub fn parse_script(script: &str) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    for (n, line) in script.lines().enumerate() {
+        let line = line.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (cmd, arg) = line.split_once(' ').unwrap_or((line, ""));
+        let step = match cmd {
+            "type" => Step::Type(arg.to_string()),
+            "key" => Step::Key(
+                arg.trim()
+                    .parse()
+                    .with_context(|| format!("line {}: invalid key", n + 1))?,
+            ),
+            "wait" => {
+                let secs: f64 = arg
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("line {}: invalid wait `{arg}`", n + 1))?;
+                if !secs.is_finite() || secs < 0.0 {
+                    bail!("line {}: wait must be a positive number of seconds", n + 1);
+                }
+                Step::Wait(Duration::from_secs_f64(secs))
+            }
+            "focus-lost" => Step::FocusLost,
+            "focus-gained" => Step::FocusGained,
+            other => bail!("line {}: unknown step `{other}`", n + 1),
+        };
+        steps.push(step);
+    }
+    Ok(steps)
+}
+
+pub struct Options {
+    pub text: Option<String>,
+    /// The language of the words, which also sets the text direction.
+    pub language: String,
+    pub time: u64,
+    pub mode_tokens: Vec<String>,
+    pub width: u16,
+    pub height: u16,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Outcome {
+    pub screen: String,
+    pub quit: bool,
+    pub finished: bool,
+    pub wpm: f64,
+    pub raw: f64,
+    pub accuracy: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digit_accuracy: Option<f64>,
+    pub lps: Vec<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frame: Option<Vec<String>>,
+}
+
+/// An app driven by scripted events and a fake clock, drawn to an in-memory terminal.
+pub struct Simulation {
+    pub app: App,
+    clock: FakeClock,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Simulation {
+    pub fn new(options: Options) -> Result<Self> {
+        let clock = FakeClock::new();
+        let app = App::new(AppConfig {
+            language: options.language,
+            top_words: 0,
+            lazy: false,
+            text_style: TextStyle::Words,
+            filter: WordFilter::default(),
+            mode_tokens: options.mode_tokens,
+            time: options.time,
+            direct: false,
+            text: options.text,
+            persist: false,
+            clock: clock.shared(),
+            notice: None,
+            settings: Settings::default(),
+        });
+        let terminal = Terminal::new(TestBackend::new(options.width, options.height))?;
+
+        let mut simulation = Simulation {
+            app,
+            clock,
+            terminal,
+        };
+        simulation.draw()?;
+        Ok(simulation)
+    }
+
+    pub fn run(&mut self, steps: &[Step]) -> Result<()> {
+        for step in steps {
+            if self.app.should_quit {
+                break;
+            }
+            self.step(step)?;
+        }
+        Ok(())
+    }
+
+    pub fn step(&mut self, step: &Step) -> Result<()> {
+        match step {
+            Step::Type(text) => {
+                for c in text.chars() {
+                    self.press(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+                }
+            }
+            Step::Key(chord) => self.press(KeyEvent::new(chord.code, chord.modifiers)),
+            Step::Wait(duration) => {
+                let mut left = *duration;
+                while !left.is_zero() {
+                    let step = left.min(TICK);
+                    self.clock.advance(step);
+                    self.app.tick();
+                    left -= step;
+                }
+            }
+            Step::FocusLost => self.app.handle_event(Event::FocusLost),
+            Step::FocusGained => self.app.handle_event(Event::FocusGained),
+        }
+        self.draw()
+    }
+
+    fn press(&mut self, key: KeyEvent) {
+        self.app.handle_event(Event::Key(key));
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        let app = &self.app;
+        self.terminal.draw(|frame| ui::render(frame, app))?;
+        Ok(())
+    }
+
+    /// The last drawn frame, one string per terminal row.
+    pub fn frame(&self) -> Vec<String> {
+        let buffer = self.terminal.backend().buffer();
+        (0..buffer.area.height)
+            .map(|y| {
+                let row: String = (0..buffer.area.width)
+                    .map(|x| buffer[(x, y)].symbol())
+                    .collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    pub fn outcome(&self, with_frame: bool) -> Outcome {
+        let session = self.app.session.as_ref();
+        let stats = session.map(|s| &s.stats);
+        Outcome {
+            screen: format!("{:?}", self.app.screen).to_lowercase(),
+            quit: self.app.should_quit,
+            finished: session.is_some_and(|s| s.is_finished()),
+            wpm: stats.map(|s| s.wpm()).unwrap_or(0.0),
+            raw: stats.map(|s| s.raw_wpm()).unwrap_or(0.0),
+            accuracy: stats.map(|s| s.accuracy()).unwrap_or(0.0),
+            digit_accuracy: stats.and_then(|s| s.digit_accuracy()),
+            lps: stats.map(|s| s.lps.clone()).unwrap_or_default(),
+            frame: with_frame.then(|| self.frame()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod simulate_tests {
+    use super::*;
+
+    fn simulation(text: &str, time: u64) -> Simulation {
+        simulation_in("english", text, time)
+    }
+
+    fn simulation_in(language: &str, text: &str, time: u64) -> Simulation {
+        crate::paths::init_for_tests();
+        Simulation::new(Options {
+            text: Some(text.to_string()),
+            language: language.to_string(),
+            time,
+            mode_tokens: vec!["normal".to_string()],
+            width: 80,
+            height: 24,
+        })
+        .unwrap()
+    }
+
+    fn run(sim: &mut Simulation, script: &str) {
+        sim.run(&parse_script(script).unwrap()).unwrap();
+    }
+
+    fn shows(sim: &Simulation, text: &str) -> bool {
+        sim.frame().iter().any(|row| row.contains(text))
+    }
+
+    #[test]
+    fn test_parse_script() {
+        let steps =
+            parse_script("# start\nkey enter\n\ntype hi there\nwait 0.5\nfocus-lost\n").unwrap();
+        assert_eq!(
+            steps,
+            vec![
+                Step::Key("enter".parse().unwrap()),
+                Step::Type("hi there".to_string()),
+                Step::Wait(Duration::from_millis(500)),
+                Step::FocusLost,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_script_errors_name_the_line() {
+        let err = parse_script("key enter\nwait soon\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert!(parse_script("jump\n").is_err());
+        assert!(parse_script("key hyper+x\n").is_err());
+    }
+
+    /// Compares the whole last frame, text and styles, with the stored
+    /// snapshot `name`.
+    fn assert_frame(sim: &Simulation, name: &str) {
+        insta::assert_debug_snapshot!(name, sim.terminal.backend().buffer());
+    }
+
+    #[test]
+    fn test_home_screen() {
+        let sim = simulation("hello world", 30);
+        assert_frame(&sim, "home");
+    }
+
+    #[test]
+    fn test_typing_screen() {
+        let mut sim = simulation("hello world", 30);
+        run(&mut sim, "key enter\ntype hel\nwait 2");
+        assert_eq!(sim.app.screen, crate::app::Screen::Typing);
+        assert_frame(&sim, "typing");
+    }
+
+    fn caret(sim: &mut Simulation) -> u16 {
+        sim.terminal.get_cursor_position().unwrap().x
+    }
+
+    #[test]
+    fn test_wide_and_combining_chars_keep_the_caret_aligned() {
+        let mut sim = simulation("日本語 ok", 30);
+        run(&mut sim, "key enter");
+        let start = caret(&mut sim);
+        run(&mut sim, "type 日本");
+        assert_eq!(caret(&mut sim), start + 4);
+        run(&mut sim, "type 語 ");
+        assert_eq!(caret(&mut sim), start + 7);
+
+        let mut sim = simulation("x\u{301}y ok", 30);
+        run(&mut sim, "key enter");
+        let start = caret(&mut sim);
+        run(&mut sim, "type x\u{301}");
+        assert_eq!(caret(&mut sim), start + 1);
+        assert_frame(&sim, "typing_combining");
+    }
+
+    #[test]
+    fn test_right_to_left_layout() {
+        let mut sim = simulation_in("hebrew", "שלום עולם", 30);
+        run(&mut sim, "key enter");
+        // words run from the right edge leftwards
+        assert_frame(&sim, "typing_right_to_left");
+        let start = caret(&mut sim);
+        run(&mut sim, "type של");
+        assert_eq!(caret(&mut sim), start - 2);
+        run(&mut sim, "type ום ");
+        assert_eq!(caret(&mut sim), start - 5);
+
+        // the direction belongs to the language, not to the letters
+        let mut sim = simulation("שלום עולם", 30);
+        run(&mut sim, "key enter");
+        let start = caret(&mut sim);
+        run(&mut sim, "type של");
+        assert_eq!(caret(&mut sim), start + 2);
+    }
+
+    #[test]
+    fn test_full_flow_home_typing_results_stats() {
+        let mut sim = simulation("hello world", 60);
+        run(&mut sim, "key enter\ntype hello\nwait 6\ntype  world\n");
+        let outcome = sim.outcome(false);
+        assert_eq!(outcome.screen, "results");
+        assert!(outcome.finished);
+        assert_eq!(outcome.wpm.round(), 22.0);
+        assert_eq!(outcome.accuracy, 100.0);
+        assert_frame(&sim, "results");
+
+        run(&mut sim, "wait 1\nkey esc\nkey p");
+        assert_eq!(sim.outcome(false).screen, "stats");
+        assert_frame(&sim, "stats");
+
+        run(&mut sim, "key esc\nkey q");
+        assert!(sim.outcome(false).quit);
+    }
+
+    #[test]
+    fn test_results_break_out_digits() {
+        let mut sim = simulation("room 101 at 9", 30);
+        run(&mut sim, "key enter\ntype room 102 at 9");
+        let outcome = sim.outcome(false);
+        assert_eq!(outcome.screen, "results");
+        assert_eq!(outcome.digit_accuracy, Some(75.0));
+        assert_frame(&sim, "results_digits");
+
+        let mut words = simulation("no digits", 30);
+        run(&mut words, "key enter\ntype no digits");
+        assert_eq!(words.outcome(false).digit_accuracy, None);
+        assert_frame(&words, "results_no_digits");
+    }
+
+    #[test]
+    fn test_results_ignore_keys_right_after_finishing() {
+        let mut sim = simulation("a", 30);
+        run(&mut sim, "key enter\ntype a\nkey esc");
+        assert_eq!(sim.outcome(false).screen, "results");
+    }
+
+    #[test]
+    fn test_settings_screen() {
+        let mut sim = simulation("hello", 30);
+        run(&mut sim, "key s");
+        assert_frame(&sim, "settings");
+        run(&mut sim, "key enter");
+        assert!(sim.app.settings.as_ref().unwrap().open);
+        assert_frame(&sim, "settings_dropdown");
+    }
+
+    #[test]
+    fn test_overlays() {
+        let mut sim = simulation("hello", 30);
+        run(&mut sim, "key ?");
+        assert_frame(&sim, "help");
+        run(&mut sim, "key x\nkey tab");
+        assert_frame(&sim, "quick");
+        run(&mut sim, "key esc\nkey :\ntype open st");
+        assert_frame(&sim, "palette");
+        run(&mut sim, "key enter");
+        assert_eq!(sim.outcome(false).screen, "stats");
+    }
+
+    #[test]
+    fn test_focus_loss_pauses() {
+        let mut sim = simulation("hello world", 30);
+        run(&mut sim, "key enter\ntype he\nwait 1\nfocus-lost\nwait 20");
+        assert!(shows(&sim, "paused"));
+        assert!(shows(&sim, "29s"));
+    }
+
+    #[test]
+    fn test_focus_gained_resumes() {
+        let mut sim = simulation("hello world", 30);
+        run(
+            &mut sim,
+            "key enter\ntype he\nwait 1\nfocus-lost\nwait 20\nfocus-gained\nwait 3",
+        );
+        assert!(!shows(&sim, "paused"));
+        assert!(shows(&sim, "26s"));
+    }
+
+    #[test]
+    fn test_quick_menu_pauses_the_timer() {
+        let mut sim = simulation("hello world", 30);
+        run(&mut sim, "key enter\ntype he\nwait 1\nkey tab\nwait 20");
+        assert!(sim.app.session.as_ref().unwrap().is_paused());
+        run(&mut sim, "key esc\nwait 3");
+        assert!(!sim.app.quick);
+        assert!(shows(&sim, "26s"));
+    }
+
+    #[test]
+    fn test_focus_gained_keeps_an_afk_pause() {
+        let mut sim = simulation("hello world", 30);
+        run(
+            &mut sim,
+            "key enter\ntype he\nwait 15\nfocus-lost\nfocus-gained\nwait 5",
+        );
+        assert!(shows(&sim, "paused"));
+    }
+
+    #[test]
+    fn test_afk_pauses_the_timer() {
+        let mut sim = simulation("hello world", 60);
+        run(&mut sim, "key enter\ntype he\nwait 5\ntype l\nwait 40");
+        assert!(shows(&sim, "paused"));
+        // only the 5 seconds spent typing count
+        assert!(shows(&sim, "55s"));
+    }
+
+    #[test]
+    fn test_repeat_uses_same_words() {
+        let mut sim = simulation("alpha beta", 30);
+        run(&mut sim, "key enter\ntype alpha b\nkey tab\nkey r");
+        let session = sim.app.session.as_ref().unwrap();
+        assert_eq!(session.cursor_word, 0);
+        assert_eq!(session.words.len(), 2);
+        assert!(shows(&sim, "alpha beta"));
+    }
+}
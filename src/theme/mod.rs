@@ -1,10 +1,11 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use dirs::home_dir;
 use include_dir::{include_dir, Dir};
 use ratatui::style::Color;
 use serde::Deserialize;
 
+use crate::paths;
+
 static BUILTIN: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/themes");
 
 pub const DEFAULT_THEME: &str = "Catppuccin Mocha";
@@ -89,7 +90,7 @@ fn builtin_themes() -> Vec<Theme> {
 }
 
 fn user_themes() -> Vec<Theme> {
-    let Some(path) = home_dir().map(|p| p.join(".config/typy/theme.toml")) else {
+    let Some(path) = paths::config_dir().map(|p| p.join("theme.toml")) else {
         return Vec::new();
     };
     let Ok(text) = std::fs::read_to_string(path) else {
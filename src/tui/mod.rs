@@ -1,18 +1,27 @@
 pub mod events;
 
 use std::io::{stdout, Stdout};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::Result;
 use crossterm::cursor;
+use crossterm::event::{
+    DisableFocusChange, EnableFocusChange, KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
+    PushKeyboardEnhancementFlags,
+};
 use crossterm::execute;
 use crossterm::terminal::{
-    disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
+    disable_raw_mode, enable_raw_mode, supports_keyboard_enhancement, EnterAlternateScreen,
+    LeaveAlternateScreen,
 };
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
 pub type Backend = CrosstermBackend<Stdout>;
 
+/// Whether the kitty keyboard protocol was enabled and has to be undone.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
 pub struct Tui {
     pub terminal: Terminal<Backend>,
 }
@@ -25,7 +34,25 @@ impl Tui {
 
     pub fn enter(&mut self) -> Result<()> {
         enable_raw_mode()?;
-        execute!(stdout(), EnterAlternateScreen, cursor::Hide)?;
+        execute!(
+            stdout(),
+            EnterAlternateScreen,
+            EnableFocusChange,
+            cursor::Hide
+        )?;
+        // Terminals speaking the kitty keyboard protocol then report Esc,
+        // Alt and Ctrl chords unambiguously and shifted keys as the char
+        // they produce, instead of legacy escape sequences.
+        if supports_keyboard_enhancement().unwrap_or(false) {
+            execute!(
+                stdout(),
+                PushKeyboardEnhancementFlags(
+                    KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES
+                        | KeyboardEnhancementFlags::REPORT_ALTERNATE_KEYS
+                )
+            )?;
+            KEYBOARD_ENHANCED.store(true, Ordering::SeqCst);
+        }
         Self::install_panic_hook();
         self.terminal.clear()?;
         Ok(())
@@ -36,9 +63,13 @@ impl Tui {
     }
 
     fn restore() -> Result<()> {
+        if KEYBOARD_ENHANCED.swap(false, Ordering::SeqCst) {
+            execute!(stdout(), PopKeyboardEnhancementFlags)?;
+        }
         disable_raw_mode()?;
         execute!(
             stdout(),
+            DisableFocusChange,
             LeaveAlternateScreen,
             cursor::SetCursorStyle::DefaultUserShape,
             cursor::Show
@@ -1,10 +1,13 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 
+use crate::clock::SharedClock;
 use crate::mode::Mode;
 use crate::scores::Stats;
-use crate::word_provider;
+use crate::word_provider::WordSource;
+
+use super::normalize;
 
 pub struct Word {
     pub target: Vec<char>,
@@ -14,7 +17,7 @@ pub struct Word {
 impl Word {
     fn new(text: &str) -> Self {
         Word {
-            target: text.chars().collect(),
+            target: normalize::nfc(text).chars().collect(),
             typed: Vec::new(),
         }
     }
@@ -26,66 +29,204 @@ pub struct TypingSession {
     pub stats: Stats,
     pub duration: u64,
     start: Option<Instant>,
+    paused_at: Option<Instant>,
+    paused_total: Duration,
+    last_input: Option<Instant>,
     sampled_secs: u64,
     finished: bool,
+    lazy: bool,
+    rtl: bool,
+    /// A dead key waiting for the letter it accents.
+    dead_key: Option<char>,
+    clock: SharedClock,
 }
 
 impl TypingSession {
-    pub fn new(mode: &Mode, language: &str) -> Result<Self> {
+    /// A session over words drawn from `source`, transformed by `mode`.
+    pub fn new(mode: &Mode, source: &mut dyn WordSource, clock: SharedClock) -> Result<Self> {
         let min_words = (mode.duration as usize).saturating_mul(5).max(60);
-        let mut list = word_provider::get_words(language, min_words)
-            .context("Failed to get words from file")?;
+        let mut list = source
+            .words(min_words)
+            .context("Failed to get words for the test")?;
         mode.transform(&mut list);
 
-        let words = list
-            .into_iter()
-            .flatten()
-            .map(|w| Word::new(&w))
-            .collect::<Vec<_>>();
+        let words = list.iter().map(|w| Word::new(w)).collect::<Vec<_>>();
+
+        Ok(TypingSession::from_words(words, mode.duration, clock))
+    }
+
+    /// A session over fixed, space separated text instead of a word list.
+    pub fn from_text(text: &str, duration: u64, clock: SharedClock) -> Self {
+        let words = text.split_whitespace().map(Word::new).collect();
+        TypingSession::from_words(words, duration, clock)
+    }
 
-        Ok(TypingSession {
+    fn from_words(words: Vec<Word>, duration: u64, clock: SharedClock) -> Self {
+        TypingSession {
             words,
             cursor_word: 0,
             stats: Stats::new(),
-            duration: mode.duration,
+            duration,
             start: None,
+            paused_at: None,
+            paused_total: Duration::ZERO,
+            last_input: None,
             sampled_secs: 0,
             finished: false,
-        })
+            lazy: false,
+            rtl: false,
+            dead_key: None,
+            clock,
+        }
+    }
+
+    /// A fresh session over the same words, for repeating a test.
+    pub fn replay(&self) -> Self {
+        let words = self
+            .words
+            .iter()
+            .map(|w| Word {
+                target: w.target.clone(),
+                typed: Vec::new(),
+            })
+            .collect();
+
+        TypingSession::from_words(words, self.duration, self.clock.clone())
+            .with_lazy(self.lazy)
+            .with_rtl(self.rtl)
+    }
+
+    /// Accepts letters typed without their accents, e.g. `e` for `é`.
+    pub fn with_lazy(mut self, lazy: bool) -> Self {
+        self.lazy = lazy;
+        self
+    }
+
+    /// Lays the words out right to left, for languages written that way.
+    pub fn with_rtl(mut self, rtl: bool) -> Self {
+        self.rtl = rtl;
+        self
+    }
+
+    pub fn is_rtl(&self) -> bool {
+        self.rtl
+    }
+
+    /// Whether `typed` counts as a correct keystroke for `target`.
+    pub fn matches(&self, typed: char, target: char) -> bool {
+        if self.lazy {
+            normalize::lazy_eq(typed, target)
+        } else {
+            typed == target
+        }
     }
 
     pub fn is_finished(&self) -> bool {
         self.finished
     }
 
-    pub fn remaining_secs(&self) -> u64 {
-        match self.start {
-            None => self.duration,
-            Some(start) => self
-                .duration
-                .saturating_sub(start.elapsed().as_secs()),
+    pub fn is_paused(&self) -> bool {
+        self.paused_at.is_some()
+    }
+
+    /// Stops the clock until the next keystroke. Does nothing before the
+    /// first keystroke or after the test has finished.
+    pub fn pause(&mut self) {
+        self.pause_at(self.clock.now());
+    }
+
+    /// Stops the clock as of the last keystroke, so the idle time that made
+    /// the test count as AFK is not counted either.
+    pub fn pause_idle(&mut self) {
+        if let Some(last) = self.last_input {
+            self.pause_at(last);
+        }
+    }
+
+    fn pause_at(&mut self, at: Instant) {
+        if self.start.is_some() && self.paused_at.is_none() && !self.finished {
+            self.paused_at = Some(at);
+        }
+    }
+
+    /// Starts the clock again. The AFK timeout counts from here, not from
+    /// the keystroke before the pause.
+    pub fn resume(&mut self) {
+        if let Some(paused_at) = self.paused_at.take() {
+            let now = self.clock.now();
+            self.paused_total += now.duration_since(paused_at);
+            self.last_input = Some(now);
+        }
+    }
+
+    /// Time spent typing so far, excluding pauses.
+    fn elapsed(&self) -> Duration {
+        let Some(start) = self.start else {
+            return Duration::ZERO;
+        };
+        let now = self.paused_at.unwrap_or_else(|| self.clock.now());
+        now.duration_since(start).saturating_sub(self.paused_total)
+    }
+
+    /// Seconds since the last keystroke while the clock is running.
+    pub fn idle_secs(&self) -> u64 {
+        match (self.last_input, self.paused_at) {
+            (Some(last), None) => self.clock.now().duration_since(last).as_secs(),
+            _ => 0,
         }
     }
 
+    fn input(&mut self) {
+        self.resume();
+        let now = self.clock.now();
+        if self.start.is_none() {
+            self.start = Some(now);
+        }
+        self.last_input = Some(now);
+    }
+
+    pub fn remaining_secs(&self) -> u64 {
+        self.duration.saturating_sub(self.elapsed().as_secs())
+    }
+
     fn current(&mut self) -> Option<&mut Word> {
         self.words.get_mut(self.cursor_word)
     }
 
+    /// Types `c` into the current word; the first keystroke starts the clock.
+    /// A spacing accent such as `´` or `^` is held as a dead key when the
+    /// next letter of the word carries that accent, and composed with the
+    /// letter typed next.
     pub fn type_char(&mut self, c: char) {
         if self.finished {
             return;
         }
-        if self.start.is_none() {
-            self.start = Some(Instant::now());
-        }
+        self.input();
 
         let idx = self.cursor_word;
-        let Some(word) = self.current() else {
+        let Some(word) = self.words.get(idx) else {
             return;
         };
-        word.typed.push(c);
 
-        self.stats.letter_count += 1;
+        if let Some(dead) = self.dead_key.take() {
+            let mark = normalize::dead_key_mark(dead);
+            match mark.and_then(|mark| normalize::compose(c, mark)) {
+                Some(composed) => self.push_char(composed),
+                None => {
+                    self.push_char(dead);
+                    self.push_char(c);
+                }
+            }
+        } else if normalize::dead_key_mark(c).is_some_and(|mark| {
+            word.target
+                .get(word.typed.len())
+                .is_some_and(|&next| normalize::has_mark(next, mark))
+        }) {
+            self.dead_key = Some(c);
+            return;
+        } else {
+            self.push_char(c);
+        }
 
         if idx == self.words.len() - 1 {
             let word = &self.words[idx];
@@ -95,10 +236,43 @@ impl TypingSession {
         }
     }
 
+    /// Adds one char to the current word. A combining accent is merged into
+    /// the char before it, and in lazy mode `ss` may stand for `ß`; neither
+    /// merge counts as another letter.
+    fn push_char(&mut self, c: char) {
+        let lazy = self.lazy;
+        let Some(word) = self.current() else {
+            return;
+        };
+        let at = word.typed.len();
+        let composed = word
+            .typed
+            .last()
+            .and_then(|last| normalize::compose(*last, c));
+        if let Some(composed) = composed {
+            word.typed[at - 1] = composed;
+        } else if lazy
+            && c == 's'
+            && word.typed.last() == Some(&'s')
+            && word.target.get(at - 1) == Some(&'ß')
+        {
+            word.typed[at - 1] = 'ß';
+        } else {
+            word.typed.push(c);
+            self.stats.letter_count += 1;
+        }
+    }
+
+    /// Moves on to the next word, or finishes on the last one.
     pub fn space(&mut self) {
         if self.finished {
             return;
         }
+        self.input();
+        // A dead key followed by space types the accent itself.
+        if let Some(dead) = self.dead_key.take() {
+            self.push_char(dead);
+        }
         if self.cursor_word + 1 >= self.words.len() {
             self.finish();
         } else {
@@ -106,10 +280,15 @@ impl TypingSession {
         }
     }
 
+    /// Deletes the last typed char, or steps back into the previous word.
     pub fn backspace(&mut self) {
         if self.finished {
             return;
         }
+        self.input();
+        if self.dead_key.take().is_some() {
+            return;
+        }
         match self.current() {
             Some(word) if !word.typed.is_empty() => {
                 word.typed.pop();
@@ -122,15 +301,14 @@ impl TypingSession {
         }
     }
 
+    /// Samples letters per second and finishes the test once time is up.
+    /// Call it regularly, e.g. from the event loop.
     pub fn tick(&mut self) {
-        let Some(start) = self.start else {
-            return;
-        };
-        if self.finished {
+        if self.start.is_none() || self.finished || self.is_paused() {
             return;
         }
 
-        let elapsed = start.elapsed().as_secs();
+        let elapsed = self.elapsed().as_secs();
         while self.sampled_secs < elapsed {
             self.stats.add_letters();
             self.sampled_secs += 1;
@@ -147,12 +325,11 @@ impl TypingSession {
         }
         self.stats.add_letters();
         let (correct, incorrect, extra, missed) = self.tally();
-        let elapsed = self
-            .start
-            .map(|s| s.elapsed().as_secs_f64())
-            .unwrap_or(0.0);
+        let elapsed = self.elapsed().as_secs_f64();
         self.stats
             .finalize(correct, incorrect, extra, missed, self.cursor_word as i32, elapsed);
+        let (digits_correct, digits_total) = self.digit_tally();
+        self.stats.record_digits(digits_correct, digits_total);
         self.finished = true;
     }
 
@@ -166,7 +343,7 @@ impl TypingSession {
         for (wi, word) in self.words.iter().enumerate().take(last + 1) {
             let common = word.target.len().min(word.typed.len());
             for i in 0..common {
-                if word.typed[i] == word.target[i] {
+                if self.matches(word.typed[i], word.target[i]) {
                     correct += 1;
                 } else {
                     incorrect += 1;
@@ -183,11 +360,34 @@ impl TypingSession {
         (correct, incorrect, extra, missed)
     }
 
+    /// Correct and total digits among the words reached so far; skipped
+    /// digits count as wrong, extra typed chars are ignored.
+    fn digit_tally(&self) -> (i32, i32) {
+        let mut correct = 0;
+        let mut total = 0;
+
+        let last = self.cursor_word.min(self.words.len().saturating_sub(1));
+        for (wi, word) in self.words.iter().enumerate().take(last + 1) {
+            let reached = if wi < self.cursor_word {
+                word.target.len()
+            } else {
+                word.typed.len().min(word.target.len())
+            };
+            for (i, target) in word.target.iter().enumerate().take(reached) {
+                if target.is_ascii_digit() {
+                    total += 1;
+                    if word.typed.get(i) == Some(target) {
+                        correct += 1;
+                    }
+                }
+            }
+        }
+
+        (correct, total)
+    }
+
     pub fn live_wpm(&self) -> u32 {
-        let Some(start) = self.start else {
-            return 0;
-        };
-        let minutes = start.elapsed().as_secs_f64() / 60.0;
+        let minutes = self.elapsed().as_secs_f64() / 60.0;
         if minutes <= 0.0 {
             return 0;
         }
@@ -196,3 +396,251 @@ impl TypingSession {
         ((chars / 5.0) / minutes).max(0.0) as u32
     }
 }
+
+#[cfg(test)]
+mod engine_tests {
+    use super::*;
+    use crate::clock::FakeClock;
+
+    fn session(text: &str, duration: u64) -> (TypingSession, FakeClock) {
+        let clock = FakeClock::new();
+        (TypingSession::from_text(text, duration, clock.shared()), clock)
+    }
+
+    fn type_str(session: &mut TypingSession, text: &str) {
+        for c in text.chars() {
+            if c == ' ' {
+                session.space();
+            } else {
+                session.type_char(c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_clock_starts_on_first_keystroke() {
+        let (mut s, clock) = session("hello world", 30);
+        clock.advance_secs(5.0);
+        s.tick();
+        assert_eq!(s.remaining_secs(), 30);
+        assert_eq!(s.live_wpm(), 0);
+
+        s.type_char('h');
+        clock.advance_secs(4.0);
+        assert_eq!(s.remaining_secs(), 26);
+    }
+
+    #[test]
+    fn test_perfect_session_finishes_on_last_char() {
+        let (mut s, clock) = session("hello world", 60);
+        type_str(&mut s, "hello");
+        clock.advance_secs(6.0);
+        type_str(&mut s, " world");
+
+        assert!(s.is_finished());
+        assert_eq!(s.stats.wpm().round() as u32, 22);
+        assert_eq!(s.stats.raw_wpm().round() as u32, 22);
+        assert_eq!(s.stats.accuracy(), 100.0);
+        assert_eq!(s.stats.lps, vec![10]);
+    }
+
+    #[test]
+    fn test_timer_expiry_samples_every_second() {
+        let (mut s, clock) = session("aaaa bbbb cccc dddd", 3);
+        type_str(&mut s, "aa");
+        clock.advance_secs(1.0);
+        s.tick();
+        type_str(&mut s, "aa b");
+        clock.advance_secs(1.0);
+        s.tick();
+        clock.advance_secs(1.0);
+        s.tick();
+
+        assert!(s.is_finished());
+        assert_eq!(s.stats.lps, vec![2, 3, 0, 0]);
+        // 5 correct chars and one space over three seconds
+        assert_eq!(s.stats.wpm().round() as u32, 24);
+        assert_eq!(s.stats.accuracy(), 100.0);
+    }
+
+    #[test]
+    fn test_errors_extra_and_missed_chars() {
+        let (mut s, clock) = session("abc def ghi jkl", 12);
+        type_str(&mut s, "abx de ghiXY ");
+        clock.advance_secs(12.0);
+        s.tick();
+
+        assert!(s.is_finished());
+        // correct: ab + de + ghi = 7, incorrect: x, extra: XY, missed: f
+        assert!((s.stats.accuracy() - 7.0 / 11.0 * 100.0).abs() < 1e-9);
+        // net = 7 correct + 3 spaces, raw = 7 + 1 + 2 + 3 spaces
+        assert!((s.stats.wpm() - 10.0).abs() < 1e-9);
+        assert!((s.stats.raw_wpm() - 13.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_digit_accuracy() {
+        let (mut s, clock) = session("call 555-0142 in 2024 ok", 30);
+        // one wrong digit in the phone number, the year is skipped halfway
+        type_str(&mut s, "call 555-0143 in 20 ok");
+        clock.advance_secs(10.0);
+        s.tick();
+
+        assert!(s.is_finished());
+        // digits: 7 in the phone number (6 right) + 4 in the year (2 right)
+        assert_eq!(s.stats.digit_accuracy(), Some(8.0 / 11.0 * 100.0));
+
+        let (mut words, _) = session("no digits", 30);
+        type_str(&mut words, "no digits");
+        assert_eq!(words.stats.digit_accuracy(), None);
+    }
+
+    #[test]
+    fn test_decomposed_text_and_input_are_composed() {
+        let (mut s, _clock) = session("cafe\u{301} né ok", 30);
+        assert_eq!(s.words[0].target, vec!['c', 'a', 'f', 'é']);
+        type_str(&mut s, "café ne\u{301} ok");
+
+        assert!(s.is_finished());
+        assert_eq!(s.words[1].typed, vec!['n', 'é']);
+        assert_eq!(s.stats.accuracy(), 100.0);
+    }
+
+    #[test]
+    fn test_lazy_accents() {
+        let clock = FakeClock::new();
+        let mut s = TypingSession::from_text("déjà Straße Fuß", 30, clock.shared()).with_lazy(true);
+        type_str(&mut s, "deja Strasse Fus");
+        assert!(s.is_finished());
+        assert_eq!(s.words[1].typed, vec!['S', 't', 'r', 'a', 'ß', 'e']);
+        assert_eq!(s.stats.accuracy(), 100.0);
+
+        let (mut strict, _) = session("déjà", 30);
+        type_str(&mut strict, "deja");
+        assert_eq!(strict.stats.accuracy(), 50.0);
+    }
+
+    #[test]
+    fn test_merged_chars_are_one_letter() {
+        let clock = FakeClock::new();
+        let mut s = TypingSession::from_text("café Straße x", 30, clock.shared()).with_lazy(true);
+        type_str(&mut s, "cafe\u{301} Stras");
+        assert_eq!(s.stats.letter_count, 9);
+        type_str(&mut s, "se");
+        assert_eq!(s.words[1].typed.iter().collect::<String>(), "Straße");
+        assert_eq!(s.stats.letter_count, 10);
+    }
+
+    #[test]
+    fn test_space_skips_and_backspace_returns() {
+        let (mut s, _clock) = session("one two", 30);
+        type_str(&mut s, "on ");
+        assert_eq!(s.cursor_word, 1);
+        s.backspace();
+        assert_eq!(s.cursor_word, 0);
+        s.backspace();
+        assert_eq!(s.words[0].typed, vec!['o']);
+    }
+
+    #[test]
+    fn test_live_wpm() {
+        let (mut s, clock) = session("hello world again", 60);
+        type_str(&mut s, "hello world");
+        clock.advance_secs(12.0);
+        // 10 correct chars + 1 space in 12 seconds
+        assert_eq!(s.live_wpm(), 11);
+    }
+
+    #[test]
+    fn test_paused_time_is_not_counted() {
+        let (mut s, clock) = session("hello world", 60);
+        type_str(&mut s, "hello");
+        clock.advance_secs(3.0);
+        s.pause();
+        assert!(s.is_paused());
+        clock.advance_secs(100.0);
+        s.tick();
+        assert_eq!(s.remaining_secs(), 57);
+        assert_eq!(s.idle_secs(), 0);
+
+        type_str(&mut s, " wor");
+        assert!(!s.is_paused());
+        clock.advance_secs(3.0);
+        type_str(&mut s, "ld");
+
+        assert!(s.is_finished());
+        assert_eq!(s.stats.wpm().round() as u32, 22);
+    }
+
+    #[test]
+    fn test_idle_time_before_afk_is_not_counted() {
+        let (mut s, clock) = session("hello world", 60);
+        type_str(&mut s, "he");
+        clock.advance_secs(5.0);
+        type_str(&mut s, "l");
+        clock.advance_secs(12.0);
+        s.pause_idle();
+        s.tick();
+        assert!(s.is_paused());
+        assert_eq!(s.remaining_secs(), 55);
+
+        clock.advance_secs(30.0);
+        type_str(&mut s, "lo");
+        clock.advance_secs(2.0);
+        assert_eq!(s.remaining_secs(), 53);
+    }
+
+    #[test]
+    fn test_resume() {
+        let (mut s, clock) = session("hello world", 60);
+        s.pause();
+        assert!(!s.is_paused(), "nothing to pause before the first keystroke");
+
+        type_str(&mut s, "he");
+        clock.advance_secs(2.0);
+        s.pause();
+        assert!(s.is_paused());
+        clock.advance_secs(20.0);
+        assert_eq!(s.remaining_secs(), 58);
+
+        s.resume();
+        assert!(!s.is_paused());
+        assert_eq!(s.idle_secs(), 0);
+        clock.advance_secs(3.0);
+        assert_eq!(s.remaining_secs(), 55);
+        assert_eq!(s.idle_secs(), 3);
+    }
+
+    #[test]
+    fn test_idle_secs() {
+        let (mut s, clock) = session("hello", 30);
+        assert_eq!(s.idle_secs(), 0);
+        s.type_char('h');
+        clock.advance_secs(7.5);
+        assert_eq!(s.idle_secs(), 7);
+        s.type_char('e');
+        assert_eq!(s.idle_secs(), 0);
+    }
+
+    #[test]
+    fn test_replay_keeps_words_and_resets_progress() {
+        let (mut s, _clock) = session("alpha beta", 30);
+        type_str(&mut s, "alpha be");
+        let replay = s.replay();
+        assert_eq!(replay.words.len(), 2);
+        assert_eq!(replay.words[1].target, vec!['b', 'e', 't', 'a']);
+        assert!(replay.words.iter().all(|w| w.typed.is_empty()));
+        assert_eq!(replay.cursor_word, 0);
+        assert_eq!(replay.remaining_secs(), 30);
+    }
+
+    #[test]
+    fn test_no_input_after_finish() {
+        let (mut s, _clock) = session("a", 30);
+        s.type_char('a');
+        assert!(s.is_finished());
+        s.type_char('b');
+        s.backspace();
+        assert_eq!(s.words[0].typed, vec!['a']);
+    }
+}
@@ -1,3 +1,4 @@
 mod engine;
+mod normalize;
 
 pub use engine::{TypingSession, Word};
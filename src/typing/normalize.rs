@@ -0,0 +1,124 @@
+//! Unicode normalization for typing tests: composing a base letter and a
+//! combining accent (NFC) and stripping accents for lazy mode.
+
+use unicode_normalization::char::{
+    compose as compose_pair, decompose_canonical, is_combining_mark,
+};
+use unicode_normalization::UnicodeNormalization;
+
+/// The precomposed form of `base` followed by the combining `mark`.
+pub(crate) fn compose(base: char, mark: char) -> Option<char> {
+    if !is_combining_mark(mark) {
+        return None;
+    }
+    compose_pair(base, mark)
+}
+
+/// The combining mark a dead key stands for, for terminals that pass the
+/// spacing accent through instead of composing it with the next letter.
+pub(crate) fn dead_key_mark(c: char) -> Option<char> {
+    let mark = match c {
+        '`' => '\u{300}',
+        '´' => '\u{301}',
+        '^' => '\u{302}',
+        '~' => '\u{303}',
+        '˘' => '\u{306}',
+        '˙' => '\u{307}',
+        '¨' => '\u{308}',
+        '˚' | '°' => '\u{30a}',
+        '˝' => '\u{30b}',
+        'ˇ' => '\u{30c}',
+        '¸' => '\u{327}',
+        '˛' => '\u{328}',
+        _ => return None,
+    };
+    Some(mark)
+}
+
+/// Whether `letter` is a base letter with `mark` as its last accent, e.g.
+/// `ê` and `\u{302}`.
+pub(crate) fn has_mark(letter: char, mark: char) -> bool {
+    let mut parts = Vec::new();
+    decompose_canonical(letter, |part| parts.push(part));
+    parts.len() > 1 && parts.last() == Some(&mark)
+}
+
+/// Composes every base letter and combining mark that has a precomposed
+/// form, so `e\u{301}` and `é` compare equal.
+pub(crate) fn nfc(text: &str) -> String {
+    text.nfc().collect()
+}
+
+/// `c` without any accents, e.g. `é` -> `e` and `Ș` -> `S`. Letters that
+/// decompose into more than a base and accents, such as Hangul, are kept.
+pub(crate) fn strip_accents(c: char) -> char {
+    let mut parts = Vec::new();
+    decompose_canonical(c, |part| parts.push(part));
+    match parts.split_first() {
+        Some((&base, marks)) if marks.iter().all(|&m| is_combining_mark(m)) => base,
+        _ => c,
+    }
+}
+
+/// Whether `typed` counts for `target` when accents are optional. `ß` may be
+/// typed as `s` (or `ss`, see the engine).
+pub(crate) fn lazy_eq(typed: char, target: char) -> bool {
+    typed == target
+        || strip_accents(typed) == strip_accents(target)
+        || (target == 'ß' && typed == 's')
+}
+
+#[cfg(test)]
+mod normalize_tests {
+    use super::*;
+
+    #[test]
+    fn test_nfc_composes_accents() {
+        assert_eq!(nfc("cafe\u{301}"), "café");
+        assert_eq!(nfc("Mädchen"), "Mädchen");
+        assert_eq!(nfc("S\u{326}tefan"), "Ștefan");
+        assert_eq!(nfc("\u{438}\u{306}"), "й");
+        assert_eq!(nfc("x\u{301}"), "x\u{301}");
+    }
+
+    #[test]
+    fn test_dead_keys() {
+        let compose_dead = |dead, c| dead_key_mark(dead).and_then(|m| compose(c, m));
+        assert_eq!(compose_dead('´', 'e'), Some('é'));
+        assert_eq!(compose_dead('^', 'A'), Some('Â'));
+        assert_eq!(compose_dead('¨', 'u'), Some('ü'));
+        assert_eq!(compose_dead('˘', 'a'), Some('ă'));
+        assert_eq!(compose_dead('¸', 's'), Some('ş'));
+        assert_eq!(compose_dead('´', 'x'), None);
+        assert_eq!(dead_key_mark('a'), None);
+    }
+
+    #[test]
+    fn test_has_mark() {
+        assert!(has_mark('ê', '\u{302}'));
+        assert!(has_mark('ș', '\u{326}'));
+        assert!(!has_mark('e', '\u{302}'));
+        assert!(!has_mark('é', '\u{302}'));
+    }
+
+    #[test]
+    fn test_strip_accents() {
+        assert_eq!(strip_accents('é'), 'e');
+        assert_eq!(strip_accents('Ñ'), 'N');
+        assert_eq!(strip_accents('ț'), 't');
+        assert_eq!(strip_accents('ş'), 's');
+        assert_eq!(strip_accents('ё'), 'е');
+        assert_eq!(strip_accents('x'), 'x');
+        assert_eq!(strip_accents('ǘ'), 'u');
+        assert_eq!(strip_accents('한'), '한');
+    }
+
+    #[test]
+    fn test_lazy_eq() {
+        assert!(lazy_eq('e', 'é'));
+        assert!(lazy_eq('E', 'È'));
+        assert!(lazy_eq('s', 'ß'));
+        assert!(!lazy_eq('e', 'É'));
+        assert!(!lazy_eq('a', 'é'));
+    }
+}
@@ -0,0 +1,53 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+
+const PANEL_WIDTH: u16 = 56;
+const KEY_COL: usize = 22;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+
+    let context = app.context();
+    let mut lines: Vec<Line> = context
+        .actions()
+        .iter()
+        .map(|action| {
+            let mut keys = format!(" {}", app.config.keymap.labels(*action));
+            while keys.chars().count() < KEY_COL {
+                keys.push(' ');
+            }
+            Line::from(vec![
+                Span::styled(
+                    keys,
+                    Style::default()
+                        .fg(theme.accent)
+                        .add_modifier(Modifier::BOLD),
+                ),
+                Span::styled(action.description(), Style::default().fg(theme.fg)),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        " ctrl+c quits from anywhere · any key closes",
+        Style::default().fg(theme.missing),
+    )));
+
+    let area = super::centered_rect(frame.area(), PANEL_WIDTH, lines.len() as u16 + 2);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(Span::styled(
+            format!(" {} keys ", context.name()),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
@@ -26,7 +26,7 @@ pub fn render(frame: &mut Frame, app: &App) {
         .split(area);
 
     render_hero(frame, root[0], app, theme);
-    render_command_bar(frame, root[1], theme);
+    render_command_bar(frame, root[1], app);
 }
 
 fn render_hero(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
@@ -75,27 +75,9 @@ fn render_hero(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     );
 }
 
-fn render_command_bar(frame: &mut Frame, area: Rect, theme: &Theme) {
-    let key = |k: &'static str| {
-        Span::styled(
-            k,
-            Style::default()
-                .fg(theme.accent)
-                .add_modifier(Modifier::BOLD),
-        )
-    };
-    let label = |t: &'static str| Span::styled(t, Style::default().fg(theme.missing));
-
-    let line = Line::from(vec![
-        key(" s "),
-        label("settings   "),
-        key(" p "),
-        label("stats   "),
-        key(" q "),
-        label("quit"),
-    ]);
+fn render_command_bar(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(
-        Paragraph::new(line).alignment(Alignment::Center),
+        Paragraph::new(super::hint_bar(app)).alignment(Alignment::Center),
         area,
     );
 }
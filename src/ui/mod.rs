@@ -1,10 +1,16 @@
+mod help;
 mod home;
+mod palette;
+mod quick;
 mod results;
 mod settings;
 mod stats;
 mod typing;
 
-use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Clear, Paragraph};
 use ratatui::Frame;
 
 use crate::app::{App, Screen};
@@ -17,6 +23,68 @@ pub fn render(frame: &mut Frame, app: &App) {
         Screen::Settings => settings::render(frame, app),
         Screen::Stats => stats::render(frame, app),
     }
+    if app.quick {
+        quick::render(frame, app);
+    }
+    if app.help {
+        help::render(frame, app);
+    }
+    if app.palette.is_some() {
+        palette::render(frame, app);
+    }
+    if let Some(notice) = app.notice.as_ref() {
+        render_notice(frame, app, notice);
+    }
+}
+
+/// The `key action` hints for the current context, driven by the keymap.
+pub(crate) fn hint_bar(app: &App) -> Line<'static> {
+    let theme = &app.theme;
+    let mut spans = Vec::new();
+    for (i, action) in app.context().hints().iter().enumerate() {
+        if i > 0 {
+            spans.push(Span::raw("   "));
+        }
+        spans.push(Span::styled(
+            format!(" {} ", app.config.keymap.label(*action)),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+        spans.push(Span::styled(
+            action.hint(),
+            Style::default().fg(theme.missing),
+        ));
+    }
+    Line::from(spans)
+}
+
+fn render_notice(frame: &mut Frame, app: &App, notice: &str) {
+    let area = Rect {
+        height: 1.min(frame.area().height),
+        ..frame.area()
+    };
+    frame.render_widget(Clear, area);
+    frame.render_widget(
+        Paragraph::new(Span::styled(
+            notice.to_string(),
+            Style::default().fg(app.theme.accent),
+        ))
+        .alignment(Alignment::Center),
+        area,
+    );
+}
+
+/// A `width` by `height` rect in the middle of `area`, shrunk to fit.
+pub(crate) fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
+    let width = width.min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
 }
 
 pub(crate) fn centered_vertical(area: Rect, height: u16) -> Rect {
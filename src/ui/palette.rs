@@ -0,0 +1,86 @@
+use ratatui::layout::Rect;
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+
+const PANEL_WIDTH: u16 = 50;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let Some(palette) = app.palette.as_ref() else {
+        return;
+    };
+    let matches = palette.matches();
+
+    let height = matches.len().max(1) as u16 + 4;
+    let screen = frame.area();
+    let width = PANEL_WIDTH.min(screen.width);
+    let area = Rect {
+        x: screen.x + (screen.width - width) / 2,
+        y: screen.y + screen.height / 5,
+        width,
+        height: height.min(screen.height.saturating_sub(screen.height / 5)),
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(Span::styled(
+            " commands ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+    let inner = block.inner(area);
+    frame.render_widget(Clear, area);
+    frame.render_widget(block, area);
+
+    let input = Line::from(vec![
+        Span::styled(
+            "> ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(palette.query.clone(), Style::default().fg(theme.fg)),
+    ]);
+    frame.render_widget(
+        Paragraph::new(input),
+        Rect {
+            height: 1.min(inner.height),
+            ..inner
+        },
+    );
+
+    let list_area = Rect {
+        y: inner.y + 2,
+        height: inner.height.saturating_sub(2),
+        ..inner
+    };
+    if matches.is_empty() {
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                "no matching command",
+                Style::default().fg(theme.missing),
+            )),
+            list_area,
+        );
+        return;
+    }
+
+    let items: Vec<ListItem> = matches
+        .iter()
+        .map(|m| ListItem::new(m.label.clone()).style(Style::default().fg(theme.fg)))
+        .collect();
+    let list = List::new(items).highlight_style(
+        Style::default()
+            .fg(theme.accent)
+            .add_modifier(Modifier::BOLD | Modifier::REVERSED),
+    );
+    let mut list_state = ListState::default();
+    list_state.select(Some(palette.cursor));
+    frame.render_stateful_widget(list, list_area, &mut list_state);
+}
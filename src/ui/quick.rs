@@ -0,0 +1,81 @@
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph};
+use ratatui::Frame;
+
+use crate::app::App;
+use crate::config::model::TIME_PRESETS;
+use crate::keymap::Action;
+use crate::settings::mode_options;
+
+const PANEL_WIDTH: u16 = 40;
+
+pub fn render(frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let key = |k: String| {
+        Span::styled(
+            k,
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )
+    };
+    let label = |t: String, active: bool| {
+        let style = if active {
+            Style::default().fg(theme.fg).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(theme.missing)
+        };
+        Span::styled(t, style)
+    };
+
+    let mut lines = vec![
+        Line::from(vec![
+            key(format!(" {:<6}", app.config.keymap.label(Action::Restart))),
+            label("restart".into(), false),
+        ]),
+        Line::from(vec![
+            key(format!(" {:<6}", app.config.keymap.label(Action::Repeat))),
+            label("repeat words".into(), false),
+        ]),
+        Line::from(""),
+    ];
+
+    for (i, time) in TIME_PRESETS.iter().enumerate() {
+        lines.push(Line::from(vec![
+            key(format!(" {}     ", i + 1)),
+            label(format!("{time}s"), *time == app.time),
+        ]));
+    }
+    lines.push(Line::from(""));
+
+    for (i, (name, tokens)) in mode_options().into_iter().enumerate() {
+        let active = tokens.len() == app.mode_tokens.len()
+            && tokens
+                .iter()
+                .all(|t| app.mode_tokens.iter().any(|m| m == t));
+        lines.push(Line::from(vec![
+            key(format!(" {}     ", TIME_PRESETS.len() + i + 1)),
+            label(name.to_string(), active),
+        ]));
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        format!(" {}  close", app.config.keymap.label(Action::Back)),
+        Style::default().fg(theme.missing),
+    )));
+
+    let area = super::centered_rect(frame.area(), PANEL_WIDTH, lines.len() as u16 + 2);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(theme.accent))
+        .title(Span::styled(
+            " quick ",
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        ));
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
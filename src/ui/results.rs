@@ -20,7 +20,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     };
     let stats = &session.stats;
 
-    let panel = centered_rect(frame.area(), PANEL_WIDTH, PANEL_HEIGHT);
+    let panel = super::centered_rect(frame.area(), PANEL_WIDTH, PANEL_HEIGHT);
 
     let rows = Layout::default()
         .direction(Direction::Vertical)
@@ -38,7 +38,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     render_banner(frame, rows[0], app, theme);
     render_headline(frame, rows[2], stats, theme);
     render_graph(frame, rows[4], stats, theme);
-    render_footer(frame, rows[6], theme, app.direct);
+    render_footer(frame, rows[6], app, theme);
 }
 
 fn render_banner(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
@@ -66,36 +66,24 @@ fn render_banner(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
     frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), area);
 }
 
-fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
-    let width = width.min(area.width);
-    let height = height.min(area.height);
-    Rect {
-        x: area.x + (area.width - width) / 2,
-        y: area.y + (area.height - height) / 2,
-        width,
-        height,
+fn render_headline(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
+    let mut metrics = vec![
+        ("wpm", safe(stats.wpm()).to_string()),
+        ("acc", format!("{}%", safe(stats.accuracy()))),
+        ("raw", safe(stats.raw_wpm()).to_string()),
+    ];
+    if let Some(digits) = stats.digit_accuracy() {
+        metrics.push(("digits", format!("{}%", safe(digits))));
     }
-}
 
-fn render_headline(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
     let cols = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage(34),
-            Constraint::Percentage(33),
-            Constraint::Percentage(33),
-        ])
+        .constraints(vec![Constraint::Ratio(1, metrics.len() as u32); metrics.len()])
         .split(area);
 
-    metric(frame, cols[0], "wpm", &safe(stats.wpm()).to_string(), theme);
-    metric(
-        frame,
-        cols[1],
-        "acc",
-        &format!("{}%", safe(stats.accuracy())),
-        theme,
-    );
-    metric(frame, cols[2], "raw", &safe(stats.raw_wpm()).to_string(), theme);
+    for (col, (label, value)) in cols.iter().zip(&metrics) {
+        metric(frame, *col, label, value, theme);
+    }
 }
 
 fn metric(frame: &mut Frame, area: Rect, label: &str, value: &str, theme: &Theme) {
@@ -175,25 +163,14 @@ fn render_graph(frame: &mut Frame, area: Rect, stats: &Stats, theme: &Theme) {
     frame.render_widget(chart, area);
 }
 
-fn render_footer(frame: &mut Frame, area: Rect, theme: &Theme, direct: bool) {
-    let key = |k: &'static str| {
-        Span::styled(
-            k,
-            Style::default()
-                .fg(theme.accent)
-                .add_modifier(Modifier::BOLD),
-        )
-    };
-    let label = |t: &'static str| Span::styled(t, Style::default().fg(theme.missing));
-    let line = if direct {
-        Line::from(label("press any key to exit"))
+fn render_footer(frame: &mut Frame, area: Rect, app: &App, theme: &Theme) {
+    let line = if app.direct {
+        Line::from(Span::styled(
+            "press any key to exit",
+            Style::default().fg(theme.missing),
+        ))
     } else {
-        Line::from(vec![
-            key(" enter "),
-            label("restart   "),
-            key(" q "),
-            label("home"),
-        ])
+        super::hint_bar(app)
     };
     frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), area);
 }
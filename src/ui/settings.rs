@@ -5,7 +5,7 @@ use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragra
 use ratatui::Frame;
 
 use crate::app::App;
-use crate::settings::SettingsState;
+use crate::settings::{SettingsState, CUSTOM_TIME};
 use crate::theme::Theme;
 
 const PANEL_WIDTH: u16 = 52;
@@ -17,14 +17,14 @@ pub fn render(frame: &mut Frame, app: &App) {
         return;
     };
 
-    let panel = render_panel(frame, state, theme);
+    let panel = render_panel(frame, app, state, theme);
 
     if state.open {
         render_popup(frame, panel, state, theme);
     }
 }
 
-fn render_panel(frame: &mut Frame, state: &SettingsState, theme: &Theme) -> Rect {
+fn render_panel(frame: &mut Frame, app: &App, state: &SettingsState, theme: &Theme) -> Rect {
     let mut lines: Vec<Line> = Vec::new();
     for (i, row) in state.rows.iter().enumerate() {
         let active = i == state.cursor;
@@ -50,10 +50,10 @@ fn render_panel(frame: &mut Frame, state: &SettingsState, theme: &Theme) -> Rect
         ]));
     }
     lines.push(Line::from(""));
-    lines.push(hint_line(theme));
+    lines.push(super::hint_bar(app));
 
     let height = lines.len() as u16 + 2;
-    let panel = centered_rect(frame.area(), PANEL_WIDTH, height);
+    let panel = super::centered_rect(frame.area(), PANEL_WIDTH, height);
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -72,7 +72,13 @@ fn render_panel(frame: &mut Frame, state: &SettingsState, theme: &Theme) -> Rect
 fn render_popup(frame: &mut Frame, panel: Rect, state: &SettingsState, theme: &Theme) {
     let row = &state.rows[state.cursor];
 
-    let width = popup_width(row.options.iter().map(|s| s.as_str()), row.label);
+    let hint = state.input.as_ref().map(|_| match &state.input_error {
+        Some(error) => (error.as_str(), theme.error),
+        None => ("90 or 2m, enter", theme.missing),
+    });
+    let hint_width = hint.map_or(0, |(h, _)| h.chars().count());
+    let width = popup_width(row.options.iter().map(|s| s.as_str()), row.label)
+        .max((hint_width as u16 + 4).min(40));
     let max_visible = frame.area().height.saturating_sub(4).min(10);
     let height = (row.options.len() as u16 + 2).min(max_visible.max(3));
 
@@ -81,10 +87,13 @@ fn render_popup(frame: &mut Frame, panel: Rect, state: &SettingsState, theme: &T
     let items: Vec<ListItem> = row
         .options
         .iter()
-        .map(|opt| ListItem::new(opt.clone()))
+        .map(|opt| match &state.input {
+            Some(input) if opt == CUSTOM_TIME => ListItem::new(format!("{input}\u{258f} s")),
+            _ => ListItem::new(opt.clone()),
+        })
         .collect();
 
-    let block = Block::default()
+    let mut block = Block::default()
         .borders(Borders::ALL)
         .border_style(Style::default().fg(theme.accent))
         .title(Span::styled(
@@ -93,6 +102,12 @@ fn render_popup(frame: &mut Frame, panel: Rect, state: &SettingsState, theme: &T
                 .fg(theme.accent)
                 .add_modifier(Modifier::BOLD),
         ));
+    if let Some((hint, color)) = hint {
+        block = block.title_bottom(Span::styled(
+            format!(" {hint} "),
+            Style::default().fg(color),
+        ));
+    }
 
     let list = List::new(items)
         .block(block)
@@ -110,13 +125,6 @@ fn render_popup(frame: &mut Frame, panel: Rect, state: &SettingsState, theme: &T
     frame.render_stateful_widget(list, area, &mut list_state);
 }
 
-fn hint_line(theme: &Theme) -> Line<'static> {
-    Line::from(Span::styled(
-        "j/k move   enter select   esc close",
-        Style::default().fg(theme.missing),
-    ))
-}
-
 fn popup_width<'a>(options: impl Iterator<Item = &'a str>, label: &str) -> u16 {
     let longest = options
         .map(|s| s.chars().count())
@@ -143,14 +151,3 @@ fn popup_rect(screen: Rect, panel: Rect, row: usize, width: u16, height: u16) ->
         height,
     }
 }
-
-fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
-    let width = width.min(area.width);
-    let height = height.min(area.height);
-    Rect {
-        x: area.x + (area.width - width) / 2,
-        y: area.y + (area.height - height) / 2,
-        width,
-        height,
-    }
-}
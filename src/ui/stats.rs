@@ -5,10 +5,10 @@ use ratatui::widgets::{Block, Borders, Cell, Paragraph, Row, Table};
 use ratatui::Frame;
 
 use crate::app::{App, StatsData};
-use crate::scores::progress::Averages;
+use crate::scores::progress::{Averages, Score};
 use crate::theme::Theme;
 
-const PANEL_WIDTH: u16 = 60;
+const PANEL_WIDTH: u16 = 68;
 
 pub fn render(frame: &mut Frame, app: &App) {
     let theme = &app.theme;
@@ -16,9 +16,10 @@ pub fn render(frame: &mut Frame, app: &App) {
         return;
     };
 
-    let rows = data.scores.len() as u16;
+    let scores = data.visible();
+    let rows = scores.len() as u16;
     let height = (rows + 9).min(frame.area().height);
-    let panel = centered_rect(frame.area(), PANEL_WIDTH, height);
+    let panel = super::centered_rect(frame.area(), PANEL_WIDTH, height);
 
     let block = Block::default()
         .borders(Borders::ALL)
@@ -42,9 +43,13 @@ pub fn render(frame: &mut Frame, app: &App) {
         ])
         .split(inner);
 
-    render_averages(frame, areas[0], &data.averages, theme);
-    render_scores(frame, areas[2], data, theme);
-    render_footer(frame, areas[3], theme);
+    let averages = data.visible_averages();
+    render_averages(frame, areas[0], &averages, theme);
+    if data.has_profiles() {
+        render_filter(frame, areas[1], data, theme);
+    }
+    render_scores(frame, areas[2], &scores, &averages, theme);
+    render_footer(frame, areas[3], app);
 }
 
 fn render_averages(frame: &mut Frame, area: Rect, averages: &Averages, theme: &Theme) {
@@ -63,8 +68,22 @@ fn render_averages(frame: &mut Frame, area: Rect, averages: &Averages, theme: &T
     frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), area);
 }
 
-fn render_scores(frame: &mut Frame, area: Rect, data: &StatsData, theme: &Theme) {
-    if data.scores.is_empty() {
+fn render_filter(frame: &mut Frame, area: Rect, data: &StatsData, theme: &Theme) {
+    let line = Line::from(vec![
+        Span::styled("profile  ", Style::default().fg(theme.missing)),
+        Span::styled(data.filter_label(), Style::default().fg(theme.fg)),
+    ]);
+    frame.render_widget(Paragraph::new(line).alignment(Alignment::Center), area);
+}
+
+fn render_scores(
+    frame: &mut Frame,
+    area: Rect,
+    scores: &[&Score],
+    averages: &Averages,
+    theme: &Theme,
+) {
+    if scores.is_empty() {
         frame.render_widget(
             Paragraph::new(Span::styled(
                 "no games played yet",
@@ -80,14 +99,14 @@ fn render_scores(frame: &mut Frame, area: Rect, data: &StatsData, theme: &Theme)
         .fg(theme.missing)
         .add_modifier(Modifier::BOLD);
     let header = Row::new(
-        ["date", "time", "wpm", "raw", "acc"]
+        ["date", "time", "wpm", "raw", "acc", "digits"]
             .into_iter()
             .map(|h| Cell::from(Line::from(h).alignment(Alignment::Center))),
     )
     .style(header_style);
 
-    let avg = &data.averages;
-    let rows = data.scores.iter().map(|score| {
+    let avg = averages;
+    let rows = scores.iter().map(|score| {
         let wpm_color = good_bad(score.wpm as f32, avg.wpm_avg.avg, theme);
         let raw_color = good_bad(score.raw as f32, avg.raw_avg.avg, theme);
         let acc_color = good_bad(score.accuracy, avg.accuracy_avg.avg, theme);
@@ -98,6 +117,13 @@ fn render_scores(frame: &mut Frame, area: Rect, data: &StatsData, theme: &Theme)
             centered(score.wpm.to_string(), wpm_color),
             centered(score.raw.to_string(), raw_color),
             centered(format!("{:.1}%", score.accuracy), acc_color),
+            centered(
+                score
+                    .digit_accuracy
+                    .map(|a| format!("{a:.1}%"))
+                    .unwrap_or_else(|| "-".to_string()),
+                theme.fg,
+            ),
         ])
     });
 
@@ -107,18 +133,15 @@ fn render_scores(frame: &mut Frame, area: Rect, data: &StatsData, theme: &Theme)
         Constraint::Length(7),
         Constraint::Length(7),
         Constraint::Length(9),
+        Constraint::Length(9),
     ];
 
     frame.render_widget(Table::new(rows, widths).header(header), area);
 }
 
-fn render_footer(frame: &mut Frame, area: Rect, theme: &Theme) {
+fn render_footer(frame: &mut Frame, area: Rect, app: &App) {
     frame.render_widget(
-        Paragraph::new(Span::styled(
-            "esc  back",
-            Style::default().fg(theme.missing),
-        ))
-        .alignment(Alignment::Center),
+        Paragraph::new(super::hint_bar(app)).alignment(Alignment::Center),
         area,
     );
 }
@@ -134,14 +157,3 @@ fn good_bad(value: f32, average: f32, theme: &Theme) -> ratatui::style::Color {
         theme.error
     }
 }
-
-fn centered_rect(area: Rect, width: u16, height: u16) -> Rect {
-    let width = width.min(area.width);
-    let height = height.min(area.height);
-    Rect {
-        x: area.x + (area.width - width) / 2,
-        y: area.y + (area.height - height) / 2,
-        width,
-        height,
-    }
-}
@@ -3,11 +3,16 @@ use ratatui::style::{Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::Paragraph;
 use ratatui::Frame;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
 
 use crate::app::App;
 use crate::theme::Theme;
 use crate::typing::{TypingSession, Word};
 
+/// Widest a line of words gets, so lines stay readable on wide terminals.
+const LINE_LENGTH: u16 = 80;
+
 pub fn render(frame: &mut Frame, app: &App) {
     let theme = &app.theme;
     let Some(session) = app.session.as_ref() else {
@@ -15,7 +20,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     };
     let area = frame.area();
 
-    let width = area.width.saturating_mul(6) / 10;
+    let width = (area.width.saturating_mul(6) / 10).min(LINE_LENGTH);
     let column = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([
@@ -41,7 +46,7 @@ pub fn render(frame: &mut Frame, app: &App) {
     render_language(frame, rows[1], &app.language, theme);
     render_status(frame, rows[3], session, theme);
     render_words(frame, rows[5], session, theme);
-    render_hint(frame, area, theme);
+    render_hint(frame, area, app);
 }
 
 fn render_language(frame: &mut Frame, area: Rect, language: &str, theme: &Theme) {
@@ -70,48 +75,93 @@ fn render_status(frame: &mut Frame, area: Rect, session: &TypingSession, theme:
             Style::default().fg(theme.missing),
         ),
     ]);
+    let line = if session.is_paused() {
+        let mut spans = line.spans;
+        spans.push(Span::styled(
+            "   paused \u{00b7} type to resume",
+            Style::default().fg(theme.error),
+        ));
+        Line::from(spans)
+    } else {
+        line
+    };
     frame.render_widget(Paragraph::new(line).alignment(Alignment::Left), area);
 }
 
+/// A word as it appears on screen: one span per grapheme cluster, in
+/// visual order, and the caret column relative to the word's first cell
+/// when the word is current.
+struct Laid {
+    spans: Vec<Span<'static>>,
+    width: usize,
+    caret: isize,
+}
+
 fn render_words(frame: &mut Frame, area: Rect, session: &TypingSession, theme: &Theme) {
     let width = area.width.max(1) as usize;
+    let rtl = session.is_rtl();
 
-    let mut lines: Vec<Line> = Vec::new();
-    let mut current: Vec<Span> = Vec::new();
+    let mut lines: Vec<Vec<Laid>> = vec![Vec::new()];
     let mut col = 0usize;
-    let mut caret_line = 0usize;
-    let mut caret_col = 0u16;
+    let mut caret_at = (0usize, 0usize);
 
     for (wi, word) in session.words.iter().enumerate() {
-        let word_len = word.target.len().max(word.typed.len());
+        let laid = lay_out(session, word, theme, rtl);
 
-        if col > 0 && col + 1 + word_len > width {
-            lines.push(Line::from(std::mem::take(&mut current)));
+        if col > 0 && col + 1 + laid.width > width {
+            lines.push(Vec::new());
             col = 0;
         }
         if col > 0 {
-            current.push(Span::raw(" "));
             col += 1;
         }
+        col += laid.width;
 
+        let line_index = lines.len() - 1;
+        let line = &mut lines[line_index];
         if wi == session.cursor_word {
-            caret_line = lines.len();
-            let within = word.typed.len().min(word_len);
-            caret_col = (col + within) as u16;
+            caret_at = (line_index, line.len());
         }
+        line.push(laid);
+    }
 
-        for i in 0..word_len {
-            let (ch, style) = char_style(word, i, theme);
-            current.push(Span::styled(ch.to_string(), style));
+    let (caret_line, caret_word) = caret_at;
+    let mut caret_col = 0usize;
+    let mut rendered: Vec<Line> = Vec::new();
+    for (li, mut words) in lines.into_iter().enumerate() {
+        if rtl {
+            words.reverse();
         }
-        col += word_len;
-    }
-    if !current.is_empty() {
-        lines.push(Line::from(current));
+        let line_width =
+            words.iter().map(|w| w.width).sum::<usize>() + words.len().saturating_sub(1);
+        let mut x = if rtl {
+            width.saturating_sub(line_width)
+        } else {
+            0
+        };
+        let mut spans = Vec::new();
+        let count = words.len();
+        for (i, laid) in words.into_iter().enumerate() {
+            let logical = if rtl { count - 1 - i } else { i };
+            if li == caret_line && logical == caret_word {
+                caret_col = x.saturating_add_signed(laid.caret);
+            }
+            if i > 0 {
+                spans.push(Span::raw(" "));
+            }
+            x += laid.width + 1;
+            spans.extend(laid.spans);
+        }
+        let alignment = if rtl {
+            Alignment::Right
+        } else {
+            Alignment::Left
+        };
+        rendered.push(Line::from(spans).alignment(alignment));
     }
 
     let window_start = caret_line.saturating_sub(1);
-    let visible: Vec<Line> = lines
+    let visible: Vec<Line> = rendered
         .into_iter()
         .skip(window_start)
         .take(area.height as usize)
@@ -120,16 +170,55 @@ fn render_words(frame: &mut Frame, area: Rect, session: &TypingSession, theme: &
 
     let cursor_row = (caret_line - window_start) as u16;
     if cursor_row < area.height {
-        let x = area.x + caret_col.min(area.width.saturating_sub(1));
+        let x = area.x + (caret_col as u16).min(area.width.saturating_sub(1));
         let y = area.y + cursor_row;
         frame.set_cursor_position((x, y));
     }
 }
 
-fn char_style(word: &Word, i: usize, theme: &Theme) -> (char, Style) {
+/// Splits the word into grapheme clusters so combining marks stay with their
+/// letter and wide chars take two columns. Right-to-left words are laid out
+/// mirrored, with the caret to the left of the typed part.
+fn lay_out(session: &TypingSession, word: &Word, theme: &Theme, rtl: bool) -> Laid {
+    let styled: Vec<(char, Style)> = (0..word.target.len().max(word.typed.len()))
+        .map(|i| char_style(session, word, i, theme))
+        .collect();
+    let text: String = styled.iter().map(|(c, _)| c).collect();
+
+    let mut clusters = Vec::new();
+    let mut typed_width = 0;
+    let mut index = 0;
+    for grapheme in text.graphemes(true) {
+        let style = styled[index].1;
+        let cluster_width = grapheme.width();
+        if index < word.typed.len() {
+            typed_width += cluster_width;
+        }
+        index += grapheme.chars().count();
+        clusters.push(Span::styled(grapheme.to_string(), style));
+    }
+
+    let width = clusters.iter().map(|s| s.content.width()).sum();
+    if rtl {
+        clusters.reverse();
+        Laid {
+            spans: clusters,
+            width,
+            caret: width as isize - typed_width as isize - 1,
+        }
+    } else {
+        Laid {
+            spans: clusters,
+            width,
+            caret: typed_width as isize,
+        }
+    }
+}
+
+fn char_style(session: &TypingSession, word: &Word, i: usize, theme: &Theme) -> (char, Style) {
     if i < word.typed.len() {
         if i < word.target.len() {
-            let ok = word.typed[i] == word.target[i];
+            let ok = session.matches(word.typed[i], word.target[i]);
             (
                 word.target[i],
                 Style::default().fg(if ok { theme.fg } else { theme.error }),
@@ -147,17 +236,13 @@ fn char_style(word: &Word, i: usize, theme: &Theme) -> (char, Style) {
     }
 }
 
-fn render_hint(frame: &mut Frame, area: Rect, theme: &Theme) {
+fn render_hint(frame: &mut Frame, area: Rect, app: &App) {
     let bar = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Min(0), Constraint::Length(1)])
         .split(area)[1];
     frame.render_widget(
-        Paragraph::new(Span::styled(
-            "esc  cancel",
-            Style::default().fg(theme.missing),
-        ))
-        .alignment(Alignment::Center),
+        Paragraph::new(super::hint_bar(app)).alignment(Alignment::Center),
         bar,
     );
 }
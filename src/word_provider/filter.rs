@@ -0,0 +1,179 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use super::list::WordList;
+
+/// Restrictions on which words of a list can show up in a test.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WordFilter {
+    pub min_length: Option<usize>,
+    pub max_length: Option<usize>,
+    /// Words may only use these characters, e.g. the home row `asdfghjkl`.
+    pub allowed_chars: Option<String>,
+    /// Words must contain at least one of these characters.
+    pub required_chars: Option<String>,
+    /// Words that never show up, compared case-insensitively.
+    pub blocklist: BTreeSet<String>,
+}
+
+impl WordFilter {
+    /// Adds the words of a file, one per line, to the blocklist.
+    pub fn with_blocklist_file(mut self, path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read blocklist {}", path.display()))?;
+        self.blocklist.extend(
+            text.lines()
+                .map(|l| l.trim().to_lowercase())
+                .filter(|l| !l.is_empty()),
+        );
+        Ok(self)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == WordFilter::default()
+    }
+
+    pub fn accepts(&self, word: &str) -> bool {
+        let lower = word.to_lowercase();
+        let length = word.chars().count();
+        self.min_length.is_none_or(|min| length >= min)
+            && self.max_length.is_none_or(|max| length <= max)
+            && self
+                .allowed_chars
+                .as_ref()
+                .is_none_or(|allowed| lower.chars().all(|c| allowed.to_lowercase().contains(c)))
+            && self
+                .required_chars
+                .as_ref()
+                .is_none_or(|required| required.to_lowercase().chars().any(|c| lower.contains(c)))
+            && !self.blocklist.contains(&lower)
+    }
+
+    /// Keeps the words of `list` this filter accepts. Fails if none are left,
+    /// naming the restrictions so the user knows what to loosen.
+    pub fn apply(&self, list: WordList) -> Result<WordList> {
+        if self.is_empty() {
+            return Ok(list);
+        }
+        let WordList {
+            words,
+            weights,
+            rtl,
+        } = list;
+        let (words, weights): (Vec<String>, Vec<Option<f64>>) = match weights {
+            Some(weights) => words
+                .into_iter()
+                .zip(weights.into_iter().map(Some))
+                .filter(|(w, _)| self.accepts(w))
+                .unzip(),
+            None => words
+                .into_iter()
+                .filter(|w| self.accepts(w))
+                .map(|w| (w, None))
+                .unzip(),
+        };
+        if words.is_empty() {
+            bail!("No words are left after filtering ({})", self.describe());
+        }
+        Ok(WordList {
+            words,
+            weights: weights.into_iter().collect(),
+            rtl,
+        })
+    }
+
+    fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(min) = self.min_length {
+            parts.push(format!("min length {min}"));
+        }
+        if let Some(max) = self.max_length {
+            parts.push(format!("max length {max}"));
+        }
+        if let Some(allowed) = &self.allowed_chars {
+            parts.push(format!("only \"{allowed}\""));
+        }
+        if let Some(required) = &self.required_chars {
+            parts.push(format!("one of \"{required}\""));
+        }
+        if !self.blocklist.is_empty() {
+            parts.push(format!("{} blocked words", self.blocklist.len()));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod filter_tests {
+    use super::*;
+
+    fn list(words: &[&str]) -> WordList {
+        WordList {
+            words: words.iter().map(|w| w.to_string()).collect(),
+            weights: None,
+            rtl: false,
+        }
+    }
+
+    #[test]
+    fn test_length_range() {
+        let filter = WordFilter {
+            min_length: Some(3),
+            max_length: Some(4),
+            ..WordFilter::default()
+        };
+        let kept = filter
+            .apply(list(&["a", "abc", "abcd", "abcde", "été"]))
+            .unwrap();
+        assert_eq!(kept.words, vec!["abc", "abcd", "été"]);
+    }
+
+    #[test]
+    fn test_allowed_and_required_chars() {
+        let filter = WordFilter {
+            allowed_chars: Some("asdfghjkl".into()),
+            required_chars: Some("GH".into()),
+            ..WordFilter::default()
+        };
+        let kept = filter
+            .apply(list(&["flash", "ghost", "Hall", "lads", "gas"]))
+            .unwrap();
+        assert_eq!(kept.words, vec!["flash", "Hall", "gas"]);
+    }
+
+    #[test]
+    fn test_blocklist_keeps_weights_aligned() {
+        let dir = std::env::temp_dir().join(format!("typy-filter-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("blocklist.txt");
+        fs::write(&file, "Beta\n\n").unwrap();
+
+        let filter = WordFilter::default().with_blocklist_file(&file).unwrap();
+        let weighted = WordList::parse("alpha\t3\nbeta\t2\ngamma\t1\n").unwrap();
+        let kept = filter.apply(weighted).unwrap();
+        assert_eq!(kept.words, vec!["alpha", "gamma"]);
+        assert_eq!(kept.weights, Some(vec![3.0, 1.0]));
+
+        assert!(WordFilter::default()
+            .with_blocklist_file(&dir.join("missing.txt"))
+            .is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_empty_pool_is_an_error() {
+        let filter = WordFilter {
+            min_length: Some(20),
+            allowed_chars: Some("xyz".into()),
+            ..WordFilter::default()
+        };
+        let err = filter.apply(list(&["short"])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No words are left after filtering (min length 20, only \"xyz\")"
+        );
+    }
+}
@@ -1,112 +1,190 @@
 use anyhow::{bail, Context, Result};
-use dirs::home_dir;
-use reqwest::blocking::get;
+use include_dir::{include_dir, Dir};
 use std::{
-    fs::{create_dir_all, write, File},
-    io::{BufRead, BufReader},
-    path::PathBuf,
-    sync::LazyLock,
+    fs,
+    path::{Path, PathBuf},
 };
 
-use rand::seq::IndexedRandom;
+use super::list::WordList;
+use super::packs;
+use crate::paths;
 
-static WORDS_DIR: LazyLock<Option<PathBuf>> = LazyLock::new(|| {
-    if cfg!(test) {
-        Some(PathBuf::from("./resources/lang/"))
-    } else {
-        home_dir().map(|p| p.join(".local/share/typy"))
-    }
-});
-const WORDS_URL: &str =
-    "https://raw.githubusercontent.com/Pazl27/typy-cli/refs/heads/master/resources/lang/";
-
-pub fn find(language: &str, lenght: i32) -> Result<Vec<String>> {
-    let Some(words_file) = WORDS_DIR
-        .as_ref()
-        .map(|p| p.join(format!("{language}.txt")))
-    else {
-        bail!("Unable to find home directory");
-    };
+static BUILTIN: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/lang");
 
-    if !words_file.exists() {
-        create_dir_all(words_file.parent().unwrap())?;
-        let language_url = format!("{WORDS_URL}{language}.txt");
-        let resp = get(&language_url)
-            .context("Failed to download words file from ".to_owned() + &language_url)?;
-        write(
-            &words_file,
-            resp.text()
-                .context("Failed to extract text from words file download")?,
-        )
-        .with_context(|| format!("Failed to save words file to {words_file:#?}"))?;
-    }
+static CORPORA: Dir = include_dir!("$CARGO_MANIFEST_DIR/resources/corpus");
 
-    let words = read_file(words_file.to_str().unwrap())?;
-    let mut word = random_word(&words);
+/// Loads the word list for `language`. A file in the data directory
+/// overrides the list bundled in the binary; nothing is downloaded.
+pub fn find(language: &str) -> Result<WordList> {
+    load(language, user_dir().as_deref())
+}
 
-    let mut fitted_words = Vec::new();
-    while check_if_fits(&word, &mut fitted_words, lenght) {
-        fitted_words.push(word.clone());
-        word = random_word(&words);
-    }
+fn load(language: &str, user_dir: Option<&Path>) -> Result<WordList> {
+    packs::check_name(language)?;
+    let user_file = user_dir.map(|dir| dir.join(format!("{language}.txt")));
+    let (text, origin) = match user_file.filter(|file| file.exists()) {
+        Some(file) => (
+            fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read words file {file:#?}"))?,
+            file.display().to_string(),
+        ),
+        None => match BUILTIN
+            .get_file(format!("{language}.txt"))
+            .and_then(|f| f.contents_utf8())
+        {
+            Some(text) => (text.to_string(), format!("bundled {language}.txt")),
+            None => bail!(
+                "Unknown language `{language}`; run `typy lang install {language}` to download it"
+            ),
+        },
+    };
+    let mut list = WordList::parse(&text).with_context(|| format!("Invalid word list {origin}"))?;
+    list.rtl = packs::is_rtl(language, user_dir);
+    Ok(list)
+}
 
-    Ok(fitted_words)
+/// Loads the text corpus for `language` along with where it came from. A
+/// file in the `corpus` folder of the data directory overrides the bundled
+/// one.
+pub fn find_corpus(language: &str) -> Result<(String, String)> {
+    corpus_in(language, user_dir().map(|d| d.join("corpus")).as_deref())
 }
 
-fn read_file(path: &str) -> Result<Vec<String>, std::io::Error> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let mut words = Vec::new();
-    for line in reader.lines() {
-        words.push(line?);
+fn corpus_in(language: &str, user_dir: Option<&Path>) -> Result<(String, String)> {
+    packs::check_name(language)?;
+    let file_name = format!("{language}.txt");
+    if let Some(file) = user_dir
+        .map(|dir| dir.join(&file_name))
+        .filter(|f| f.exists())
+    {
+        let text = fs::read_to_string(&file)
+            .with_context(|| format!("Failed to read corpus {file:#?}"))?;
+        return Ok((text, file.display().to_string()));
     }
-    Ok(words)
+    match CORPORA.get_file(&file_name).and_then(|f| f.contents_utf8()) {
+        Some(text) => Ok((text.to_string(), format!("bundled corpus/{file_name}"))),
+        None => match user_dir {
+            Some(dir) => bail!(
+                "No text corpus for `{language}`; add one at {}",
+                dir.join(&file_name).display()
+            ),
+            None => bail!(
+                "No text corpus for `{language}`; add one as corpus/{file_name} in the data directory"
+            ),
+        },
+    }
+}
+
+/// Names of the languages bundled in the binary.
+pub fn bundled() -> Vec<String> {
+    BUILTIN
+        .files()
+        .filter(|f| f.path().extension().and_then(|e| e.to_str()) == Some("txt"))
+        .filter_map(|f| f.path().file_stem().and_then(|s| s.to_str()))
+        .map(str::to_string)
+        .collect()
 }
 
-fn random_word(words: &[String]) -> String {
-    let mut rng = rand::rng();
-    let word = words.choose(&mut rng).unwrap();
-    word.to_string()
+/// Names of the languages in the user's words directory.
+pub fn installed() -> Vec<String> {
+    user_dir().as_deref().map(languages_in).unwrap_or_default()
 }
 
-fn check_if_fits(word: &str, fitted_words: &mut [String], lenght: i32) -> bool {
-    let list_length: i32 = fitted_words
-        .iter()
-        .map(|s| s.chars().count() as i32)
-        .sum::<i32>()
-        + word.chars().count() as i32;
+/// Names of the `.txt` word lists in `dir`.
+pub fn languages_in(dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("txt"))
+        .filter_map(|path| path.file_stem().and_then(|s| s.to_str()).map(str::to_string))
+        .collect()
+}
 
-    if list_length > lenght {
-        return false;
-    }
-    true
+/// Contents of a file bundled from `resources/lang`.
+pub fn bundled_file(file: &str) -> Option<&'static [u8]> {
+    BUILTIN.get_file(file).map(|f| f.contents())
+}
+
+/// The user's words directory: the data directory.
+pub fn user_dir() -> Option<PathBuf> {
+    paths::data_dir()
 }
 
 #[cfg(test)]
 mod finder_tests {
 
     use super::*;
+    use std::fs::{create_dir_all, write};
+
+    #[test]
+    fn test_find() {
+        let list = load("english", None).unwrap();
+        assert_eq!(list.words.len(), 7776);
+        assert!(list.words.iter().all(|w| !w.is_empty()));
+        assert!(!list.is_ranked());
+    }
 
     #[test]
-    fn test_read_file() {
-        let words = read_file("./resources/lang/english.txt").unwrap();
-        assert_eq!(words.len(), 7776);
+    fn test_bundled_languages() {
+        let languages = bundled();
+        for language in ["english", "german", "russian"] {
+            assert!(languages.iter().any(|l| l == language));
+            assert!(!load(language, None).unwrap().words.is_empty());
+        }
+        let err = load("klingon", None).unwrap_err();
+        assert!(err.to_string().contains("typy lang install klingon"));
     }
 
     #[test]
-    fn test_random_word() {
-        let words = vec!["Hello".to_string(), "World".to_string()];
-        let word = random_word(&words);
-        assert!(word == "Hello" || word == "World");
+    fn test_user_file_overrides_bundled() {
+        let dir = std::env::temp_dir().join(format!("typy-finder-{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        write(dir.join("english.txt"), "alpha\n\nbeta\n").unwrap();
+        write(dir.join("custom.txt"), "gamma\t2\n").unwrap();
+        write(dir.join("broken.txt"), "delta\tlots\n").unwrap();
+
+        assert_eq!(load("english", Some(&dir)).unwrap().words, vec!["alpha", "beta"]);
+        let custom = load("custom", Some(&dir)).unwrap();
+        assert_eq!(custom.weights, Some(vec![2.0]));
+        assert_eq!(
+            load("german", Some(&dir)).unwrap(),
+            load("german", None).unwrap()
+        );
+        let err = load("broken", Some(&dir)).unwrap_err();
+        assert!(format!("{err:#}").contains("Invalid word list"));
+
+        // a language from the config can't reach files outside the directory
+        let words = dir.join("words");
+        create_dir_all(&words).unwrap();
+        for name in ["../english", "../../english", "a/b"] {
+            let err = load(name, Some(&words)).unwrap_err();
+            assert!(err.to_string().contains("not a valid language name"), "{name}");
+            assert!(corpus_in(name, Some(&words)).is_err());
+        }
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 
     #[test]
-    fn test_check_if_fits() {
-        let word = "Hello".to_string();
-        let mut fitted_words = Vec::new();
-        let lenght = 5;
-        assert!(check_if_fits(&word, &mut fitted_words, lenght));
-        fitted_words.push("Hello".to_string());
-        assert!(!check_if_fits(&word, &mut fitted_words, lenght));
+    fn test_user_corpus_overrides_bundled() {
+        let dir = std::env::temp_dir().join(format!("typy-corpus-{}", std::process::id()));
+        create_dir_all(&dir).unwrap();
+        write(dir.join("english.txt"), "A small corpus.").unwrap();
+
+        let (text, origin) = corpus_in("english", Some(&dir)).unwrap();
+        assert_eq!(text, "A small corpus.");
+        assert!(origin.ends_with("english.txt"));
+        let (_, origin) = corpus_in("german", Some(&dir)).unwrap();
+        assert_eq!(origin, "bundled corpus/german.txt");
+        let err = corpus_in("russian", Some(&dir)).unwrap_err();
+        let missing = dir.join("russian.txt");
+        assert!(err.to_string().contains(&missing.display().to_string()));
+        let err = corpus_in("russian", None).unwrap_err();
+        assert!(err.to_string().contains("corpus/russian.txt in the data directory"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
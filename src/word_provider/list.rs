@@ -0,0 +1,135 @@
+use anyhow::{bail, Result};
+
+use super::finder;
+use super::source::{RandomWords, WeightedWords, WordSource};
+
+/// A word list as loaded from a language file.
+///
+/// Each line holds one word, optionally followed by a tab and a weight such
+/// as its frequency in a corpus. Lists with weights are ranked, heaviest
+/// first, and sampled in proportion to the weights.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordList {
+    pub words: Vec<String>,
+    pub weights: Option<Vec<f64>>,
+    /// Whether the language is written right to left, as its manifest says.
+    pub rtl: bool,
+}
+
+impl WordList {
+    /// Loads the list for `language`; see [`finder::find`] for where from.
+    pub fn load(language: &str) -> Result<Self> {
+        finder::find(language)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut words = Vec::new();
+        let mut weights = Vec::new();
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match line.split_once('\t') {
+                Some((word, weight)) => {
+                    let Some(weight) = weight.trim().parse::<f64>().ok().filter(|w| *w >= 0.0)
+                    else {
+                        bail!("Line {}: invalid weight `{}`", i + 1, weight.trim());
+                    };
+                    words.push(word.trim().to_string());
+                    weights.push(weight);
+                }
+                None => words.push(line.trim().to_string()),
+            }
+        }
+
+        if !weights.is_empty() && weights.len() != words.len() {
+            bail!("Either every word or no word must have a weight");
+        }
+        Ok(WordList {
+            words,
+            weights: (!weights.is_empty()).then_some(weights),
+            rtl: false,
+        })
+    }
+
+    pub fn is_ranked(&self) -> bool {
+        self.weights.is_some()
+    }
+
+    /// Keeps only the `n` heaviest words; `0` keeps everything. Unranked
+    /// lists have no notion of frequency and are left as they are.
+    pub fn top(mut self, n: usize) -> Self {
+        let Some(weights) = self.weights.take() else {
+            return self;
+        };
+        let mut ranked: Vec<(String, f64)> = self.words.into_iter().zip(weights).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        if n > 0 {
+            ranked.truncate(n);
+        }
+        let (words, weights) = ranked.into_iter().unzip();
+        WordList {
+            words,
+            weights: Some(weights),
+            rtl: self.rtl,
+        }
+    }
+
+    /// A source drawing from this list: weighted if the list is ranked,
+    /// uniform otherwise.
+    pub fn into_source(self) -> Result<Box<dyn WordSource>> {
+        Ok(match self.weights {
+            Some(weights) => Box::new(WeightedWords::new(
+                self.words.into_iter().zip(weights).collect(),
+            )?),
+            None => Box::new(RandomWords::new(self.words)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod list_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plain() {
+        let list = WordList::parse("apple\n\npomme de terre\n").unwrap();
+        assert_eq!(list.words, vec!["apple", "pomme de terre"]);
+        assert!(!list.is_ranked());
+        assert_eq!(list.clone().top(1), list);
+    }
+
+    #[test]
+    fn test_parse_weighted() {
+        let list = WordList::parse("of\t30\nthe\t60.5\nrare\t0\n").unwrap();
+        assert_eq!(list.weights, Some(vec![30.0, 60.5, 0.0]));
+
+        let top = list.top(2);
+        assert_eq!(top.words, vec!["the", "of"]);
+        assert_eq!(top.weights, Some(vec![60.5, 30.0]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = WordList::parse("a\t1\nb\tmany\n").unwrap_err();
+        assert!(err.to_string().contains("Line 2"));
+        assert!(WordList::parse("a\t-1\n").is_err());
+        assert!(WordList::parse("a\t1\nb\n").is_err());
+    }
+
+    #[test]
+    fn test_weighted_sampling() {
+        let list = WordList::parse("common\t1\nnever\t0\n").unwrap();
+        let words = list.into_source().unwrap().words(200).unwrap();
+        assert!(words.iter().all(|w| w == "common"));
+    }
+
+    #[test]
+    fn test_bundled_ranked_list() {
+        crate::paths::init_for_tests();
+        let list = WordList::load("english_200").unwrap();
+        assert!(list.is_ranked());
+        assert_eq!(list.words.len(), 200);
+        assert_eq!(list.top(10).words[0], "the");
+    }
+}
@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use rand::seq::IndexedRandom;
+
+use super::{finder, WordSource};
+use crate::mode::{capitalise, end_sentence, PunctuationRules};
+
+/// Pseudo-sentences from a word-level Markov chain: each word is followed by
+/// a word that followed it somewhere in the corpus, so the text reads like
+/// the language without repeating the corpus verbatim.
+pub struct MarkovText {
+    vocab: Vec<String>,
+    /// Words that open a sentence in the corpus.
+    starts: Vec<usize>,
+    /// Every word seen after a word; `None` ends the sentence.
+    next: HashMap<usize, Vec<Option<usize>>>,
+    /// How each sentence of the corpus ends: `.`, `!` or `?`.
+    ends: Vec<char>,
+    rules: PunctuationRules,
+}
+
+impl MarkovText {
+    /// Builds the chain from plain text. Punctuation within sentences is
+    /// dropped, and a capitalised sentence opener is lowercased when the
+    /// corpus also uses it in lowercase, so German nouns keep their capital
+    /// letter.
+    pub fn new(corpus: &str) -> Result<Self> {
+        let mut ends = Vec::new();
+        let sentences: Vec<Vec<&str>> = corpus
+            .split_inclusive(['.', '!', '?', '…'])
+            .filter_map(|s| {
+                let words: Vec<&str> = s.split_whitespace().filter_map(clean).collect();
+                if words.is_empty() {
+                    return None;
+                }
+                let end = s.chars().last().filter(|c| matches!(c, '!' | '?'));
+                ends.push(end.unwrap_or('.'));
+                Some(words)
+            })
+            .collect();
+
+        let lowercase: HashSet<&str> = sentences
+            .iter()
+            .flat_map(|s| s.iter().skip(1))
+            .copied()
+            .filter(|w| w.chars().next().is_some_and(char::is_lowercase))
+            .collect();
+
+        let mut chain = MarkovText {
+            vocab: Vec::new(),
+            starts: Vec::new(),
+            next: HashMap::new(),
+            ends,
+            rules: PunctuationRules::default(),
+        };
+        let mut index: HashMap<String, usize> = HashMap::new();
+
+        for sentence in sentences {
+            let mut previous = None;
+            for (i, word) in sentence.into_iter().enumerate() {
+                let mut word = word.to_string();
+                if i == 0 {
+                    let lower = word.to_lowercase();
+                    if lowercase.contains(lower.as_str()) {
+                        word = lower;
+                    }
+                }
+                let id = *index.entry(word.clone()).or_insert_with(|| {
+                    chain.vocab.push(word);
+                    chain.vocab.len() - 1
+                });
+                match previous {
+                    Some(prev) => chain.next.entry(prev).or_default().push(Some(id)),
+                    None => chain.starts.push(id),
+                }
+                previous = Some(id);
+            }
+            if let Some(last) = previous {
+                chain.next.entry(last).or_default().push(None);
+            }
+        }
+
+        if chain.vocab.len() < 2 {
+            bail!("Corpus is too small to build sentences from");
+        }
+        Ok(chain)
+    }
+
+    /// The chain for `language`, built from its corpus in the data
+    /// directory or the one bundled in the binary.
+    pub fn load(language: &str) -> Result<Self> {
+        let (text, origin) = finder::find_corpus(language)?;
+        let mut chain =
+            MarkovText::new(&text).map_err(|e| e.context(format!("Invalid corpus {origin}")))?;
+        chain.rules = PunctuationRules::for_language(language);
+        Ok(chain)
+    }
+}
+
+/// The word without surrounding punctuation, keeping inner apostrophes and
+/// hyphens as in `don't` or `l'homme`.
+fn clean(token: &str) -> Option<&str> {
+    let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+    (!word.is_empty()).then_some(word)
+}
+
+impl WordSource for MarkovText {
+    /// Sentences start with a capital letter and end with the punctuation
+    /// sentences in the corpus end with.
+    fn words(&mut self, min_words: usize) -> Result<Vec<String>> {
+        let mut rng = rand::rng();
+        let mut words: Vec<String> = Vec::with_capacity(min_words);
+        let mut current = None;
+        let mut start = 0;
+        while words.len() < min_words {
+            let id = match current {
+                Some(id) => id,
+                None => {
+                    start = words.len();
+                    *self.starts.choose(&mut rng).unwrap_or(&0)
+                }
+            };
+            let mut word = self.vocab[id].clone();
+            if current.is_none() {
+                capitalise(&mut word);
+            }
+            words.push(word);
+            current = self
+                .next
+                .get(&id)
+                .and_then(|next| next.choose(&mut rng))
+                .copied()
+                .flatten();
+            if current.is_none() {
+                let end = self.ends.choose(&mut rng).copied().unwrap_or('.');
+                end_sentence(&mut words, start, end, self.rules);
+            }
+        }
+        Ok(words)
+    }
+}
+
+#[cfg(test)]
+mod markov_tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_follows_the_corpus() {
+        let corpus = "The cat sat on the mat. The dog sat on the rug! Der Hund lief.";
+        let mut chain = MarkovText::new(corpus).unwrap();
+        assert!(chain.vocab.iter().any(|w| w == "the"));
+        assert!(!chain.vocab.iter().any(|w| w == "The"));
+        assert!(chain.vocab.iter().any(|w| w == "Der"));
+
+        let words = chain.words(200).unwrap();
+        assert_eq!(words.len(), 200);
+        for pair in words.windows(2) {
+            if pair[0] == "sat" {
+                assert_eq!(pair[1], "on");
+            }
+            if pair[0] == "on" {
+                assert_eq!(pair[1], "the");
+            }
+        }
+    }
+
+    #[test]
+    fn test_sentences_are_capitalised_and_punctuated() {
+        let corpus = "the cat sat on the mat. did the dog sit? the dog ran!";
+        let mut chain = MarkovText::new(corpus).unwrap();
+        assert_eq!(chain.ends, ['.', '?', '!']);
+
+        let words = chain.words(300).unwrap();
+        let is_end = |w: &str| w.ends_with(['.', '?', '!']);
+        assert!(words[0].starts_with(char::is_uppercase));
+        assert!(words.iter().any(|w| is_end(w)));
+        for pair in words.windows(2) {
+            // the corpus is lowercase, so only sentence openers are capitalised
+            assert_eq!(is_end(&pair[0]), pair[1].starts_with(char::is_uppercase));
+        }
+    }
+
+    #[test]
+    fn test_sentence_ends_follow_the_language() {
+        let mut chain = MarkovText::new("tu viens ? oui !").unwrap();
+        chain.rules = PunctuationRules::for_language("french");
+        let words = chain.words(50).unwrap();
+        assert!(words.iter().any(|w| w == "?" || w == "!"));
+        assert!(!words.iter().any(|w| w.len() > 1 && w.ends_with(['?', '!'])));
+
+        let mut chain = MarkovText::new("vienes? si.").unwrap();
+        chain.rules = PunctuationRules::for_language("spanish");
+        for word in chain.words(50).unwrap() {
+            assert_eq!(word.starts_with('¿'), word.ends_with('?'), "{word}");
+        }
+    }
+
+    #[test]
+    fn test_punctuation_is_dropped() {
+        let chain = MarkovText::new("\"Well,\" she said, \"don't go.\"").unwrap();
+        assert_eq!(chain.vocab, vec!["Well", "she", "said", "don't", "go"]);
+    }
+
+    #[test]
+    fn test_too_small_corpus() {
+        assert!(MarkovText::new("").is_err());
+        assert!(MarkovText::new("Hello.").is_err());
+    }
+
+    #[test]
+    fn test_bundled_corpora() {
+        crate::paths::init_for_tests();
+        for language in ["english", "german", "french", "spanish"] {
+            let mut chain = MarkovText::load(language).unwrap();
+            assert_eq!(chain.words(100).unwrap().len(), 100);
+        }
+        let err = MarkovText::load("klingon").err().unwrap();
+        assert!(err.to_string().contains("corpus/klingon.txt"));
+    }
+}
@@ -1,65 +1,70 @@
+mod checksum;
+mod filter;
 mod finder;
+mod list;
+mod markov;
+mod packs;
+mod source;
 
-use anyhow::Result;
-use finder::find;
 use std::collections::BTreeSet;
-use std::path::PathBuf;
+use std::str::FromStr;
 
-const LENGTH: i32 = 70;
+use anyhow::{bail, Result};
 
-pub fn get_words(language: &str, min_words: usize) -> Result<Vec<Vec<String>>> {
-    let mut words = Vec::new();
-    let mut count = 0;
-    while count < min_words {
-        let line = find(language, LENGTH)?;
-        count += line.len();
-        words.push(line);
-    }
-    Ok(words)
+pub use filter::WordFilter;
+pub use list::WordList;
+pub use markov::MarkovText;
+pub use packs::{PackInfo, Packs, DEFAULT_MIRROR};
+pub use source::{
+    FileText, Generator, Passages, RandomWords, WeightedWords, WordSource, PASSAGE_SEPARATOR,
+};
+
+/// How the words of a test are put together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TextStyle {
+    /// Random words from the language's word list.
+    #[default]
+    Words,
+    /// Pseudo-sentences from a Markov chain over the language's corpus.
+    Sentences,
 }
 
-pub fn available_languages() -> Vec<String> {
-    let mut dirs: Vec<PathBuf> = Vec::new();
-    if let Some(home) = dirs::home_dir() {
-        dirs.push(home.join(".local/share/typy"));
-    }
-    dirs.push(PathBuf::from("./resources/lang"));
+impl TextStyle {
+    pub const ALL: &'static [TextStyle] = &[TextStyle::Words, TextStyle::Sentences];
+    pub const NAMES: &'static [&'static str] = &["words", "sentences"];
 
-    let mut languages = BTreeSet::new();
-    for dir in dirs {
-        let Ok(entries) = std::fs::read_dir(dir) else {
-            continue;
-        };
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("txt") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    languages.insert(stem.to_string());
-                }
-            }
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextStyle::Words => "words",
+            TextStyle::Sentences => "sentences",
         }
     }
-
-    if languages.is_empty() {
-        languages.insert("english".to_string());
-    }
-    languages.into_iter().collect()
 }
 
-#[cfg(test)]
-mod word_provider_tests {
-    use super::*;
-
-    #[test]
-    fn test_get_words() {
-        let words = get_words("english", 150).unwrap();
+impl FromStr for TextStyle {
+    type Err = anyhow::Error;
 
-        let total: usize = words.iter().map(|line| line.len()).sum();
-        assert!(total >= 150);
-
-        for line in &words {
-            let length: i32 = line.iter().map(|w| w.chars().count() as i32).sum();
-            assert!(length <= LENGTH);
+    fn from_str(input: &str) -> Result<Self> {
+        match input {
+            "words" => Ok(TextStyle::Words),
+            "sentences" => Ok(TextStyle::Sentences),
+            other => bail!("Unknown text style `{other}`; use `words` or `sentences`"),
         }
     }
 }
+
+/// Every language that can be played: bundled lists plus user installed ones.
+pub fn available_languages() -> Vec<String> {
+    let mut languages: BTreeSet<String> = finder::bundled().into_iter().collect();
+    languages.extend(finder::installed());
+    languages.into_iter().collect()
+}
+
+/// How many words the list of `language` ranks; `None` if it has no
+/// weights or can't be loaded.
+pub fn ranked_size(language: &str) -> Option<usize> {
+    WordList::load(language)
+        .ok()
+        .filter(WordList::is_ranked)
+        .map(|list| list.words.len())
+}
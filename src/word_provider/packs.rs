@@ -0,0 +1,415 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::checksum::sha256_hex;
+use super::finder;
+
+/// Where `typy lang install <name>` downloads from unless a mirror is configured.
+pub const DEFAULT_MIRROR: &str =
+    "https://raw.githubusercontent.com/Pazl27/typy-cli/refs/heads/master/resources/lang";
+
+const MANIFEST: &str = "manifest.toml";
+const INSTALLED: &str = "installed.toml";
+
+#[derive(Serialize, Deserialize, Clone)]
+struct Entry {
+    sha256: String,
+    words: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// Written right to left, like Arabic and Hebrew.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    rtl: bool,
+}
+
+type Manifest = BTreeMap<String, Entry>;
+
+/// What `typy lang list` and `typy lang info` report about one language.
+#[derive(Debug)]
+pub struct PackInfo {
+    pub name: String,
+    pub bundled: bool,
+    pub path: Option<PathBuf>,
+    pub source: String,
+    pub words: usize,
+    pub sha256: String,
+    /// Whether the list matches its manifest checksum; `None` if it has none.
+    pub verified: Option<bool>,
+}
+
+/// The word lists in a user's words directory, on top of the bundled ones.
+pub struct Packs {
+    dir: PathBuf,
+}
+
+impl Packs {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Packs { dir: dir.into() }
+    }
+
+    /// The packs in the data directory.
+    pub fn user() -> Result<Self> {
+        finder::user_dir()
+            .map(Packs::new)
+            .context("Unable to find the data directory; set XDG_DATA_HOME or TYPY_DATA_DIR")
+    }
+
+    pub fn list(&self) -> Result<Vec<PackInfo>> {
+        let mut names = finder::bundled();
+        names.extend(finder::languages_in(&self.dir));
+        names.sort();
+        names.dedup();
+        names.iter().map(|name| self.info(name)).collect()
+    }
+
+    pub fn info(&self, name: &str) -> Result<PackInfo> {
+        let path = self.file(name);
+        if path.exists() {
+            let data = fs::read(&path).with_context(|| format!("Failed to read {path:#?}"))?;
+            let sha256 = sha256_hex(&data);
+            let recorded = self.installed()?.remove(name);
+            return Ok(PackInfo {
+                name: name.to_string(),
+                bundled: bundled_list(name).is_some(),
+                source: recorded
+                    .as_ref()
+                    .and_then(|e| e.source.clone())
+                    .unwrap_or_else(|| "added by hand".to_string()),
+                verified: recorded.map(|e| e.sha256 == sha256),
+                words: count_words(&data),
+                sha256,
+                path: Some(path),
+            });
+        }
+
+        let Some(data) = bundled_list(name) else {
+            bail!("Unknown language `{name}`");
+        };
+        let sha256 = sha256_hex(data);
+        Ok(PackInfo {
+            name: name.to_string(),
+            bundled: true,
+            path: None,
+            source: "bundled".to_string(),
+            words: count_words(data),
+            verified: bundled_manifest()?.get(name).map(|e| e.sha256 == sha256),
+            sha256,
+        })
+    }
+
+    /// Installs a language by name from `mirror`, which is a URL or a
+    /// directory holding a `manifest.toml`.
+    pub fn install(&self, name: &str, mirror: &str) -> Result<PackInfo> {
+        check_name(name)?;
+        let manifest: Manifest = parse_manifest(&fetch(mirror, MANIFEST)?)
+            .with_context(|| format!("Invalid {MANIFEST} on {mirror}"))?;
+        let Some(mut entry) = manifest.get(name).cloned() else {
+            bail!("`{name}` is not listed in {mirror}/{MANIFEST}");
+        };
+        let data = fetch(mirror, &format!("{name}.txt"))?;
+        let sha256 = sha256_hex(&data);
+        if sha256 != entry.sha256 {
+            bail!(
+                "Checksum mismatch for `{name}`: expected {}, got {sha256}",
+                entry.sha256
+            );
+        }
+        entry.source = Some(format!("{}/{name}.txt", mirror.trim_end_matches('/')));
+        self.save(name, &data, entry)
+    }
+
+    /// Installs the word list in `file`, named after the file.
+    pub fn install_file(&self, file: &Path) -> Result<PackInfo> {
+        let Some(name) = file.file_stem().and_then(|s| s.to_str()) else {
+            bail!("Cannot name a language after {file:#?}");
+        };
+        let data = fs::read(file).with_context(|| format!("Failed to read {file:#?}"))?;
+        let source = fs::canonicalize(file).unwrap_or_else(|_| file.to_path_buf());
+        let entry = Entry {
+            sha256: sha256_hex(&data),
+            words: count_words(&data),
+            source: Some(source.display().to_string()),
+            rtl: false,
+        };
+        self.save(name, &data, entry)
+    }
+
+    fn save(&self, name: &str, data: &[u8], entry: Entry) -> Result<PackInfo> {
+        check_name(name)?;
+        if count_words(data) == 0 {
+            bail!("Word list `{name}` is empty");
+        }
+        fs::create_dir_all(&self.dir)
+            .with_context(|| format!("Failed to create {:#?}", self.dir))?;
+        let path = self.file(name);
+        fs::write(&path, data).with_context(|| format!("Failed to save {path:#?}"))?;
+
+        let mut installed = self.installed()?;
+        installed.insert(name.to_string(), entry);
+        self.save_installed(&installed)?;
+        self.info(name)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        check_name(name)?;
+        let path = self.file(name);
+        if !path.exists() {
+            if bundled_list(name).is_some() {
+                bail!("`{name}` is bundled with typy and cannot be removed");
+            }
+            bail!("`{name}` is not installed");
+        }
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {path:#?}"))?;
+
+        let mut installed = self.installed()?;
+        if installed.remove(name).is_some() {
+            self.save_installed(&installed)?;
+        }
+        Ok(())
+    }
+
+    fn file(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{name}.txt"))
+    }
+
+    fn installed(&self) -> Result<Manifest> {
+        match fs::read(self.dir.join(INSTALLED)) {
+            Ok(data) => parse_manifest(&data).with_context(|| format!("Invalid {INSTALLED}")),
+            Err(_) => Ok(Manifest::new()),
+        }
+    }
+
+    fn save_installed(&self, installed: &Manifest) -> Result<()> {
+        let text = toml::to_string(installed)?;
+        fs::write(self.dir.join(INSTALLED), text)
+            .with_context(|| format!("Failed to save {INSTALLED}"))
+    }
+}
+
+/// Whether `name` is written right to left: from `installed.toml` for a list
+/// in `user_dir`, from the bundled manifest otherwise.
+pub(super) fn is_rtl(name: &str, user_dir: Option<&Path>) -> bool {
+    let manifest = match user_dir.map(Packs::new) {
+        Some(packs) if packs.file(name).exists() => packs.installed(),
+        _ => bundled_manifest(),
+    };
+    manifest.is_ok_and(|m| m.get(name).is_some_and(|e| e.rtl))
+}
+
+/// Refuses names that would put a list outside the words directory.
+pub(super) fn check_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        bail!("`{name}` is not a valid language name");
+    }
+    Ok(())
+}
+
+fn bundled_list(name: &str) -> Option<&'static [u8]> {
+    finder::bundled_file(&format!("{name}.txt"))
+}
+
+fn bundled_manifest() -> Result<Manifest> {
+    parse_manifest(finder::bundled_file(MANIFEST).unwrap_or_default())
+}
+
+fn parse_manifest(data: &[u8]) -> Result<Manifest> {
+    Ok(toml::from_str(std::str::from_utf8(data)?)?)
+}
+
+fn fetch(mirror: &str, file: &str) -> Result<Vec<u8>> {
+    if mirror.starts_with("http://") || mirror.starts_with("https://") {
+        let url = format!("{}/{file}", mirror.trim_end_matches('/'));
+        let bytes = reqwest::blocking::get(&url)
+            .and_then(|r| r.error_for_status())
+            .and_then(|r| r.bytes())
+            .with_context(|| format!("Failed to download {url}"))?;
+        Ok(bytes.to_vec())
+    } else {
+        let path = Path::new(mirror).join(file);
+        fs::read(&path).with_context(|| format!("Failed to read {path:#?}"))
+    }
+}
+
+fn count_words(data: &[u8]) -> usize {
+    String::from_utf8_lossy(data)
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .count()
+}
+
+#[cfg(test)]
+mod packs_tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("typy-packs-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn mirror_manifest(name: &str, data: &str) -> String {
+        format!(
+            "[{name}]\nsha256 = \"{}\"\nwords = {}\n",
+            sha256_hex(data.as_bytes()),
+            count_words(data.as_bytes())
+        )
+    }
+
+    /// Serves `files` over HTTP on a local port until `requests` were answered.
+    fn serve(files: Vec<(&'static str, String)>, requests: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().take(requests) {
+                let mut stream = stream.unwrap();
+                let mut request = String::new();
+                BufReader::new(&stream).read_line(&mut request).unwrap();
+                let path = request.split_whitespace().nth(1).unwrap_or("/");
+                let response = match files.iter().find(|(f, _)| path == format!("/lang/{f}")) {
+                    Some((_, body)) => format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                        body.len()
+                    ),
+                    None => {
+                        "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_string()
+                    }
+                };
+                stream.write_all(response.as_bytes()).unwrap();
+            }
+        });
+        format!("http://{addr}/lang")
+    }
+
+    #[test]
+    fn test_bundled_lists_match_manifest() {
+        crate::paths::init_for_tests();
+        let packs = Packs::new(temp_dir("bundled"));
+        let list = packs.list().unwrap();
+        assert_eq!(list.len(), finder::bundled().len());
+        for pack in list {
+            assert_eq!(pack.source, "bundled");
+            assert_eq!(pack.verified, Some(true), "{} checksum", pack.name);
+            assert_eq!(pack.words, finder::find(&pack.name).unwrap().words.len());
+            let entry = &bundled_manifest().unwrap()[&pack.name];
+            assert_eq!(pack.words, entry.words);
+        }
+    }
+
+    #[test]
+    fn test_direction_comes_from_the_manifest() {
+        assert!(is_rtl("hebrew", None));
+        assert!(is_rtl("arabic", None));
+        assert!(!is_rtl("japanese", None));
+
+        // an installed list keeps the direction its mirror gave it
+        let dir = temp_dir("rtl");
+        let mirror = dir.join("mirror");
+        fs::create_dir_all(&mirror).unwrap();
+        fs::write(mirror.join("yiddish.txt"), "שפּראַך\n").unwrap();
+        let entry = mirror_manifest("yiddish", "שפּראַך\n") + "rtl = true\n";
+        fs::write(mirror.join(MANIFEST), entry).unwrap();
+        let packs = Packs::new(dir.join("words"));
+        packs.install("yiddish", mirror.to_str().unwrap()).unwrap();
+        assert!(is_rtl("yiddish", Some(&dir.join("words"))));
+        assert!(!is_rtl("yiddish", None));
+    }
+
+    #[test]
+    fn test_install_from_path_and_remove() {
+        let dir = temp_dir("path");
+        let packs = Packs::new(dir.join("words"));
+        let file = dir.join("klingon.txt");
+        fs::write(&file, "qapla\n\nbatlh\n").unwrap();
+
+        // a file is only installed when asked to, never instead of a download
+        let source = file.to_str().unwrap();
+        assert!(packs.install(source, dir.to_str().unwrap()).is_err());
+
+        let info = packs.install_file(&file).unwrap();
+        assert_eq!(info.name, "klingon");
+        assert_eq!(info.words, 2);
+        assert!(!info.bundled);
+        assert_eq!(info.verified, Some(true));
+        assert!(packs.list().unwrap().iter().any(|p| p.name == "klingon"));
+
+        fs::write(packs.file("klingon"), "tampered\n").unwrap();
+        assert_eq!(packs.info("klingon").unwrap().verified, Some(false));
+
+        packs.remove("klingon").unwrap();
+        assert!(packs.info("klingon").is_err());
+        assert!(packs.remove("klingon").is_err());
+        let err = packs.remove("english").unwrap_err();
+        assert!(err.to_string().contains("bundled"));
+    }
+
+    #[test]
+    fn test_names_stay_in_the_words_directory() {
+        let dir = temp_dir("names");
+        fs::write(dir.join("outside.txt"), "a\n").unwrap();
+        let packs = Packs::new(dir.join("words"));
+        for name in ["../outside", "a/b", "a\\b", "..", ""] {
+            let err = packs.remove(name).unwrap_err();
+            assert!(err.to_string().contains("not a valid"), "{name}");
+            assert!(packs.install(name, dir.to_str().unwrap()).is_err());
+        }
+        assert!(dir.join("outside.txt").exists());
+    }
+
+    #[test]
+    fn test_install_from_mirror_dir() {
+        let dir = temp_dir("dir");
+        let mirror = dir.join("mirror");
+        fs::create_dir_all(&mirror).unwrap();
+        fs::write(mirror.join("dutch.txt"), "fiets\nkaas\n").unwrap();
+        fs::write(
+            mirror.join(MANIFEST),
+            mirror_manifest("dutch", "fiets\nkaas\n") + &mirror_manifest("frisian", "x\n"),
+        )
+        .unwrap();
+        fs::write(mirror.join("frisian.txt"), "not what the manifest says\n").unwrap();
+
+        let packs = Packs::new(dir.join("words"));
+        let info = packs.install("dutch", mirror.to_str().unwrap()).unwrap();
+        assert_eq!(info.words, 2);
+        assert!(info.source.ends_with("dutch.txt"));
+
+        let err = packs
+            .install("frisian", mirror.to_str().unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("Checksum mismatch"));
+        assert!(!packs.file("frisian").exists());
+
+        let err = packs
+            .install("welsh", mirror.to_str().unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("not listed"));
+    }
+
+    #[test]
+    fn test_install_from_http_mirror() {
+        let words = "hallo\nwelt\nbaum\n".to_string();
+        let mirror = serve(
+            vec![
+                (MANIFEST, mirror_manifest("swiss", &words)),
+                ("swiss.txt", words),
+            ],
+            2,
+        );
+
+        let packs = Packs::new(temp_dir("http"));
+        let info = packs.install("swiss", &mirror).unwrap();
+        assert_eq!(info.words, 3);
+        assert_eq!(info.source, format!("{mirror}/swiss.txt"));
+        assert_eq!(info.verified, Some(true));
+        assert_eq!(finder::languages_in(&packs.dir), vec!["swiss".to_string()]);
+    }
+}
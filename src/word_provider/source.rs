@@ -0,0 +1,241 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+use rand::distr::weighted::WeightedIndex;
+use rand::distr::Distribution;
+use rand::seq::IndexedRandom;
+
+
+/// Where the words of a test come from.
+pub trait WordSource {
+    /// Returns the words of the next test. Endless sources return at least
+    /// `min_words`; finite ones like a quote may return fewer.
+    fn words(&mut self, min_words: usize) -> Result<Vec<String>>;
+}
+
+/// Uniformly random words from a list, e.g. a language file.
+pub struct RandomWords {
+    words: Vec<String>,
+}
+
+impl RandomWords {
+    pub fn new(words: Vec<String>) -> Result<Self> {
+        if words.is_empty() {
+            bail!("Word list is empty");
+        }
+        Ok(RandomWords { words })
+    }
+}
+
+impl WordSource for RandomWords {
+    fn words(&mut self, min_words: usize) -> Result<Vec<String>> {
+        let mut rng = rand::rng();
+        Ok((0..min_words)
+            .filter_map(|_| self.words.choose(&mut rng).cloned())
+            .collect())
+    }
+}
+
+/// Random words where each word is drawn in proportion to its weight.
+pub struct WeightedWords {
+    words: Vec<String>,
+    weights: WeightedIndex<f64>,
+}
+
+impl WeightedWords {
+    pub fn new(words: Vec<(String, f64)>) -> Result<Self> {
+        let weights = WeightedIndex::new(words.iter().map(|(_, w)| *w))
+            .context("Word weights must be positive and finite")?;
+        Ok(WeightedWords {
+            words: words.into_iter().map(|(w, _)| w).collect(),
+            weights,
+        })
+    }
+}
+
+impl WordSource for WeightedWords {
+    fn words(&mut self, min_words: usize) -> Result<Vec<String>> {
+        let mut rng = rand::rng();
+        Ok((0..min_words)
+            .map(|_| self.words[self.weights.sample(&mut rng)].clone())
+            .collect())
+    }
+}
+
+/// One randomly picked passage per test, such as a quote or a code snippet.
+/// The line between two passages in a passages file.
+pub const PASSAGE_SEPARATOR: &str = "---";
+
+pub struct Passages {
+    passages: Vec<String>,
+}
+
+impl Passages {
+    pub fn new(passages: Vec<String>) -> Result<Self> {
+        let passages: Vec<String> = passages
+            .into_iter()
+            .map(|p| p.trim().to_string())
+            .filter(|p| !p.is_empty())
+            .collect();
+        if passages.is_empty() {
+            bail!("No passages to pick from");
+        }
+        Ok(Passages { passages })
+    }
+
+    /// Reads passages separated by lines holding only `---`; see
+    /// [`Passages::parse`].
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read passages from {}", path.display()))?;
+        Passages::parse(&text)
+    }
+
+    /// Splits `text` at lines holding only [`PASSAGE_SEPARATOR`], so a
+    /// passage may span several paragraphs. `\r\n` line ends work too.
+    pub fn parse(text: &str) -> Result<Self> {
+        let lines: Vec<&str> = text.lines().collect();
+        Passages::new(
+            lines
+                .split(|line| line.trim() == PASSAGE_SEPARATOR)
+                .map(|passage| passage.join("\n"))
+                .collect(),
+        )
+    }
+}
+
+impl WordSource for Passages {
+    fn words(&mut self, _min_words: usize) -> Result<Vec<String>> {
+        let passage = self
+            .passages
+            .choose(&mut rand::rng())
+            .map(String::as_str)
+            .unwrap_or_default();
+        Ok(passage.split_whitespace().map(str::to_string).collect())
+    }
+}
+
+/// A fixed text typed from start to end.
+pub struct FileText {
+    text: String,
+}
+
+impl FileText {
+    pub fn new(text: impl Into<String>) -> Self {
+        FileText { text: text.into() }
+    }
+
+    pub fn from_path(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read text from {}", path.display()))?;
+        Ok(FileText::new(text))
+    }
+}
+
+impl WordSource for FileText {
+    fn words(&mut self, _min_words: usize) -> Result<Vec<String>> {
+        let words: Vec<String> = self.text.split_whitespace().map(str::to_string).collect();
+        if words.is_empty() {
+            bail!("Text is empty");
+        }
+        Ok(words)
+    }
+}
+
+/// Words produced by a function, for synthetic drills.
+pub struct Generator<F: FnMut() -> String> {
+    next: F,
+}
+
+impl<F: FnMut() -> String> Generator<F> {
+    pub fn new(next: F) -> Self {
+        Generator { next }
+    }
+}
+
+impl<F: FnMut() -> String> WordSource for Generator<F> {
+    fn words(&mut self, min_words: usize) -> Result<Vec<String>> {
+        Ok((0..min_words).map(|_| (self.next)()).collect())
+    }
+}
+
+#[cfg(test)]
+mod source_tests {
+    use super::*;
+
+    fn strings(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn test_random_words() {
+        let mut source = RandomWords::new(strings(&["a", "b"])).unwrap();
+        let words = source.words(50).unwrap();
+        assert_eq!(words.len(), 50);
+        assert!(words.iter().all(|w| w == "a" || w == "b"));
+        assert!(RandomWords::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_random_words_for_language() {
+        crate::paths::init_for_tests();
+        let list = crate::word_provider::WordList::load("english").unwrap();
+        let mut source = RandomWords::new(list.words).unwrap();
+        assert_eq!(source.words(150).unwrap().len(), 150);
+    }
+
+    #[test]
+    fn test_weighted_words() {
+        let mut source =
+            WeightedWords::new(vec![("often".into(), 1.0), ("never".into(), 0.0)]).unwrap();
+        assert!(source.words(100).unwrap().iter().all(|w| w == "often"));
+        assert!(WeightedWords::new(vec![("x".into(), -1.0)]).is_err());
+        assert!(WeightedWords::new(Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_passages() {
+        let mut source = Passages::new(strings(&["to be or not to be", "  "])).unwrap();
+        assert_eq!(
+            source.words(100).unwrap(),
+            strings(&["to", "be", "or", "not", "to", "be"])
+        );
+        assert!(Passages::new(strings(&["", "\n"])).is_err());
+    }
+
+    #[test]
+    fn test_passages_file() {
+        let text = "First paragraph,\r\n\r\nsecond paragraph.\r\n---\r\n\
+                    fn main() {\r\n\r\n    run();\r\n}\r\n --- \r\n\r\n";
+        let source = Passages::parse(text).unwrap();
+        assert_eq!(
+            source.passages,
+            strings(&[
+                "First paragraph,\n\nsecond paragraph.",
+                "fn main() {\n\n    run();\n}"
+            ])
+        );
+        assert!(Passages::parse("---\n\n---\n").is_err());
+    }
+
+    #[test]
+    fn test_file_text() {
+        let mut source = FileText::new("fn main() {\n    run();\n}");
+        assert_eq!(
+            source.words(1).unwrap(),
+            strings(&["fn", "main()", "{", "run();", "}"])
+        );
+        assert!(FileText::new(" \n").words(1).is_err());
+    }
+
+    #[test]
+    fn test_generator() {
+        let mut n = 0;
+        let mut source = Generator::new(|| {
+            n += 1;
+            n.to_string()
+        });
+        assert_eq!(source.words(3).unwrap(), strings(&["1", "2", "3"]));
+    }
+}